@@ -0,0 +1,438 @@
+// src/apvts.rs
+use crate::context::Context;
+use crate::create_files::is_identifier;
+use clap::ValueEnum;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The regions `jumake add parameter` edits: the parameter layout in PluginProcessor.cpp, the
+// parameter IDs in PluginProcessor.h, the editor's controls in PluginEditor.h and its
+// constructor, and their bounds in PluginEditor::resized(). The --with-apvts variants of the
+// AudioPlugin template contain them.
+pub const PARAMETERS_BEGIN_MARKER: &str = "// JUMAKE_PARAMETERS_BEGIN";
+pub const PARAMETERS_END_MARKER: &str = "// JUMAKE_PARAMETERS_END";
+pub const PARAMETER_IDS_BEGIN_MARKER: &str = "// JUMAKE_PARAMETER_IDS_BEGIN";
+pub const PARAMETER_IDS_END_MARKER: &str = "// JUMAKE_PARAMETER_IDS_END";
+pub const CONTROLS_BEGIN_MARKER: &str = "// JUMAKE_CONTROLS_BEGIN";
+pub const CONTROLS_END_MARKER: &str = "// JUMAKE_CONTROLS_END";
+pub const LAYOUT_BEGIN_MARKER: &str = "// JUMAKE_LAYOUT_BEGIN";
+pub const LAYOUT_END_MARKER: &str = "// JUMAKE_LAYOUT_END";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ParameterType {
+    Float,
+    Bool,
+    Choice,
+    Int,
+}
+
+impl ParameterType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Float => "float",
+            Self::Bool => "bool",
+            Self::Choice => "choice",
+            Self::Int => "int",
+        }
+    }
+}
+
+// The editor control `jumake add parameter --attach` creates for a parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ParameterControl {
+    Slider,
+    Button,
+    Combo,
+}
+
+// A parameter for `jumake add parameter`.
+#[derive(Debug, Clone)]
+pub struct ParameterSpec {
+    pub id: String,
+    pub parameter_type: ParameterType,
+    // "<min>:<max>" for float and int parameters.
+    pub range: Option<String>,
+    // A number, true/false, or a choice given by name or index.
+    pub default: Option<String>,
+    pub choices: Vec<String>,
+    pub attach: Option<ParameterControl>,
+}
+
+// A source file edited by `jumake add parameter`, relative to the project directory.
+pub struct FileEdit {
+    pub path: PathBuf,
+    pub old_content: String,
+    pub new_content: String,
+}
+
+pub fn add_parameter(context: &Context, spec: &ParameterSpec) -> Result<(), Box<dyn Error>> {
+    for edit in plan_parameter(context, spec)? {
+        fs::write(context.project_path.join(&edit.path), &edit.new_content)?;
+        println!("Updated file: {}", edit.path.display());
+    }
+    println!("parameter '{}' added successfully!", spec.id);
+    Ok(())
+}
+
+// Work out the edits of `add_parameter` without touching the project.
+pub fn plan_parameter(
+    context: &Context,
+    spec: &ParameterSpec,
+) -> Result<Vec<FileEdit>, Box<dyn Error>> {
+    if context.template_name.as_deref() != Some("AudioPlugin") {
+        return Err("Parameters can only be added to AudioPlugin projects".into());
+    }
+    if !is_identifier(&spec.id) {
+        return Err(format!(
+            "Parameter id '{}' must be a C++ identifier (letters, digits and '_', not a keyword)",
+            spec.id
+        )
+        .into());
+    }
+    let declaration = parameter_declaration(spec)?;
+
+    let src = Path::new("src");
+    let mut header = SourceEdit::read(context, &src.join("PluginProcessor.h"))?;
+    let id_line = format!("inline constexpr auto {} = \"{}\";", spec.id, spec.id);
+    let ids = header.region(PARAMETER_IDS_BEGIN_MARKER, PARAMETER_IDS_END_MARKER)?;
+    if ids.contains(&format!("auto {} =", spec.id)) {
+        return Err(format!("Parameter '{}' already exists", spec.id).into());
+    }
+    header.insert(
+        PARAMETER_IDS_BEGIN_MARKER,
+        PARAMETER_IDS_END_MARKER,
+        &id_line,
+    )?;
+
+    let mut processor = SourceEdit::read(context, &src.join("PluginProcessor.cpp"))?;
+    processor.insert(PARAMETERS_BEGIN_MARKER, PARAMETERS_END_MARKER, &declaration)?;
+    let mut edits = vec![header.finish(), processor.finish()];
+
+    if let Some(control) = spec.attach {
+        let (component, attachment, member) = match (control, spec.parameter_type) {
+            (ParameterControl::Slider, ParameterType::Float | ParameterType::Int) => (
+                "juce::Slider",
+                "SliderAttachment",
+                format!("{}Slider", spec.id),
+            ),
+            (ParameterControl::Button, ParameterType::Bool) => (
+                "juce::ToggleButton",
+                "ButtonAttachment",
+                format!("{}Button", spec.id),
+            ),
+            (ParameterControl::Combo, ParameterType::Choice) => (
+                "juce::ComboBox",
+                "ComboBoxAttachment",
+                format!("{}Box", spec.id),
+            ),
+            _ => {
+                return Err(format!(
+                    "A {} parameter cannot be attached to a {}; use a slider for float and int, \
+                     a button for bool and a combo for choice parameters",
+                    spec.parameter_type.as_str(),
+                    control.to_possible_value().unwrap().get_name()
+                )
+                .into())
+            }
+        };
+        let attachment_type = format!("juce::AudioProcessorValueTreeState::{}", attachment);
+        let attachment_member = format!("{}Attachment", spec.id);
+
+        let mut editor_header = SourceEdit::read(context, &src.join("PluginEditor.h"))?;
+        editor_header.insert(
+            CONTROLS_BEGIN_MARKER,
+            CONTROLS_END_MARKER,
+            &format!(
+                "{} {};\nstd::unique_ptr<{}> {};",
+                component, member, attachment_type, attachment_member
+            ),
+        )?;
+
+        let mut editor = SourceEdit::read(context, &src.join("PluginEditor.cpp"))?;
+        let mut setup = match control {
+            ParameterControl::Slider => String::new(),
+            ParameterControl::Button => format!(
+                "{}.setButtonText (\"{}\");\n",
+                member,
+                escape(&display_name(&spec.id))
+            ),
+            ParameterControl::Combo => format!(
+                "{}.addItemList (processorRef.parameters.getParameter (ParameterIDs::{})->getAllValueStrings(), 1);\n",
+                member, spec.id
+            ),
+        };
+        setup.push_str(&format!(
+            "addAndMakeVisible ({});\n{} = std::make_unique<{}> (processorRef.parameters, ParameterIDs::{}, {});",
+            member, attachment_member, attachment_type, spec.id, member
+        ));
+        editor.insert(CONTROLS_BEGIN_MARKER, CONTROLS_END_MARKER, &setup)?;
+        let mut layout = String::new();
+        if !editor
+            .region(LAYOUT_BEGIN_MARKER, LAYOUT_END_MARKER)?
+            .contains("auto controls")
+        {
+            layout.push_str("auto controls = getLocalBounds().reduced (20);\n");
+        }
+        layout.push_str(&format!(
+            "{}.setBounds (controls.removeFromTop (40));",
+            member
+        ));
+        editor.insert(LAYOUT_BEGIN_MARKER, LAYOUT_END_MARKER, &layout)?;
+
+        edits.push(editor_header.finish());
+        edits.push(editor.finish());
+    }
+    Ok(edits)
+}
+
+// The `layout.add (...)` statement for a parameter in createParameterLayout().
+fn parameter_declaration(spec: &ParameterSpec) -> Result<String, String> {
+    let applies = |option: &str, given: bool, types: &[ParameterType]| {
+        if given && !types.contains(&spec.parameter_type) {
+            Err(format!(
+                "{} does not apply to {} parameters",
+                option,
+                spec.parameter_type.as_str()
+            ))
+        } else {
+            Ok(())
+        }
+    };
+    applies(
+        "--range",
+        spec.range.is_some(),
+        &[ParameterType::Float, ParameterType::Int],
+    )?;
+    applies(
+        "--choices",
+        !spec.choices.is_empty(),
+        &[ParameterType::Choice],
+    )?;
+
+    let (class, arguments) = match spec.parameter_type {
+        ParameterType::Float | ParameterType::Int => {
+            let range = spec.range.as_deref().ok_or(format!(
+                "A {} parameter needs --range <min>:<max>",
+                spec.parameter_type.as_str()
+            ))?;
+            let (min, max) = parse_range(range)?;
+            let default = match &spec.default {
+                Some(default) => default
+                    .parse::<f64>()
+                    .map_err(|_| format!("Default value '{}' is not a number", default))?,
+                None => min,
+            };
+            if default < min || default > max {
+                return Err(format!(
+                    "Default value {} is outside the range {}",
+                    default, range
+                ));
+            }
+            if spec.parameter_type == ParameterType::Float {
+                (
+                    "AudioParameterFloat",
+                    format!(
+                        "juce::NormalisableRange<float> ({}, {}), {}",
+                        float_literal(min),
+                        float_literal(max),
+                        float_literal(default)
+                    ),
+                )
+            } else {
+                if [min, max, default].iter().any(|value| value.fract() != 0.0) {
+                    return Err(format!(
+                        "The range and default of int parameter '{}' must be whole numbers",
+                        spec.id
+                    ));
+                }
+                (
+                    "AudioParameterInt",
+                    format!("{}, {}, {}", min, max, default),
+                )
+            }
+        }
+        ParameterType::Bool => {
+            let default = match spec.default.as_deref() {
+                None | Some("false" | "off" | "0") => "false",
+                Some("true" | "on" | "1") => "true",
+                Some(default) => {
+                    return Err(format!(
+                        "Default value '{}' of a bool parameter must be true or false",
+                        default
+                    ))
+                }
+            };
+            ("AudioParameterBool", default.to_string())
+        }
+        ParameterType::Choice => {
+            if spec.choices.is_empty() {
+                return Err(String::from(
+                    "A choice parameter needs --choices <a>,<b>,...",
+                ));
+            }
+            let index = match &spec.default {
+                None => 0,
+                Some(default) => spec
+                    .choices
+                    .iter()
+                    .position(|choice| choice == default)
+                    .or_else(|| {
+                        default
+                            .parse::<usize>()
+                            .ok()
+                            .filter(|index| *index < spec.choices.len())
+                    })
+                    .ok_or(format!(
+                        "Default value '{}' is not one of the choices {}",
+                        default,
+                        spec.choices.join(", ")
+                    ))?,
+            };
+            let choices: Vec<String> = spec
+                .choices
+                .iter()
+                .map(|choice| format!("\"{}\"", escape(choice)))
+                .collect();
+            (
+                "AudioParameterChoice",
+                format!("juce::StringArray {{ {} }}, {}", choices.join(", "), index),
+            )
+        }
+    };
+
+    let head = format!(
+        "layout.add (std::make_unique<juce::{}> (juce::ParameterID {{ ParameterIDs::{}, 1 }}, \"{}\",",
+        class,
+        spec.id,
+        escape(&display_name(&spec.id))
+    );
+    // Argument lists continue on a second line, aligned with the first argument; a single
+    // default value stays on the first.
+    if !arguments.contains(", ") {
+        return Ok(format!("{} {}));", head, arguments));
+    }
+    let align = head.find("juce::ParameterID").unwrap_or_default();
+    Ok(format!("{}\n{}{}));", head, " ".repeat(align), arguments))
+}
+
+fn parse_range(range: &str) -> Result<(f64, f64), String> {
+    let error = || {
+        format!(
+            "Range '{}' must look like <min>:<max>, e.g. 20:20000",
+            range
+        )
+    };
+    let (min, max) = range.split_once(':').ok_or_else(error)?;
+    let min: f64 = min.trim().parse().map_err(|_| error())?;
+    let max: f64 = max.trim().parse().map_err(|_| error())?;
+    if min >= max {
+        return Err(format!(
+            "Range '{}' must have a minimum below its maximum",
+            range
+        ));
+    }
+    Ok((min, max))
+}
+
+// E.g. 20 -> "20.0f", 0.5 -> "0.5f".
+fn float_literal(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.1}f", value)
+    } else {
+        format!("{}f", value)
+    }
+}
+
+// The name hosts show for a parameter id, e.g. "filterCutoff" or "filter_cutoff" -> "Filter Cutoff".
+fn display_name(id: &str) -> String {
+    let mut words: Vec<String> = Vec::new();
+    let mut previous_lower = false;
+    for c in id.chars() {
+        if c == '_' {
+            words.push(String::new());
+            previous_lower = false;
+            continue;
+        }
+        if words.is_empty() || (c.is_ascii_uppercase() && previous_lower) {
+            words.push(String::new());
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        words.last_mut().unwrap().push(c);
+    }
+    words
+        .iter()
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            first.to_string() + chars.as_str()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Escape a string for a C++ string literal.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// A project file being edited between JUMAKE markers.
+struct SourceEdit {
+    path: PathBuf,
+    old_content: String,
+    content: String,
+}
+
+impl SourceEdit {
+    fn read(context: &Context, path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(context.project_path.join(path))
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Ok(SourceEdit {
+            path: path.to_path_buf(),
+            old_content: content.clone(),
+            content,
+        })
+    }
+
+    // The text between the `begin` and `end` markers.
+    fn region(&self, begin: &str, end: &str) -> Result<&str, String> {
+        let (start, stop) = self.find_region(begin, end)?;
+        Ok(&self.content[start..stop])
+    }
+
+    // Append `lines` to the region, indented like its end marker.
+    fn insert(&mut self, begin: &str, end: &str, lines: &str) -> Result<(), String> {
+        let (_, stop) = self.find_region(begin, end)?;
+        let line_start = self.content[..stop].rfind('\n').map_or(0, |idx| idx + 1);
+        let indent = self.content[line_start..stop].to_string();
+        let text: String = lines
+            .lines()
+            .map(|line| format!("{}{}\n", indent, line))
+            .collect();
+        self.content.insert_str(line_start, &text);
+        Ok(())
+    }
+
+    fn find_region(&self, begin: &str, end: &str) -> Result<(usize, usize), String> {
+        let missing = || {
+            format!(
+                "{} has no {} ... {} region; was the project created with --with-apvts?",
+                self.path.display(),
+                begin,
+                end
+            )
+        };
+        let start = self.content.find(begin).ok_or_else(missing)? + begin.len();
+        let stop = start + self.content[start..].find(end).ok_or_else(missing)?;
+        Ok((start, stop))
+    }
+
+    fn finish(self) -> FileEdit {
+        FileEdit {
+            path: self.path,
+            old_content: self.old_content,
+            new_content: self.content,
+        }
+    }
+}
@@ -12,7 +12,7 @@ pub fn build_project(context: &Context) -> Result<(), Box<dyn std::error::Error>
     println!("in '{}'...", context.build_type);
 
     // Create the build directory if it doesn't exist
-    let build_dir = context.project_path.join("jumake_build"); // New build directory name
+    let build_dir = context.project_path.join(&context.build_dir);
     fs::create_dir_all(&build_dir)?;
 
     // Run CMake to generate the build files
@@ -59,7 +59,21 @@ pub fn build_project(context: &Context) -> Result<(), Box<dyn std::error::Error>
     Ok(())
 }
 
+// Templates without an executable, and those whose executable is a console program rather
+// than an app bundle.
+const LIBRARY_TEMPLATES: &[&str] = &["StaticLibrary"];
+const CONSOLE_TEMPLATES: &[&str] = &["ConsoleApp", "JuceModule"];
+
 pub fn run_project(context: &Context) -> Result<(), Box<dyn std::error::Error>> {
+    let template_name = context.template_name.as_deref().unwrap_or_default();
+    if LIBRARY_TEMPLATES.contains(&template_name) {
+        return Err(format!(
+            "A {} project has no executable to run; use `jumake build` instead.",
+            template_name
+        )
+        .into());
+    }
+
     // First, build the project
     if let Err(e) = build_project(context) {
         return Err(format!("Failed to build the project: {}", e).into());
@@ -71,11 +85,11 @@ pub fn run_project(context: &Context) -> Result<(), Box<dyn std::error::Error>>
     let executable_path = find_executable(context)?;
 
     // Run the executable
-    if cfg!(target_os = "macos") && context.template_name.as_deref() != Some("ConsoleApp") {
+    if cfg!(target_os = "macos") && !is_console_template(context) {
         Command::new("open").arg(executable_path).status()?;
     } else {
         Command::new(executable_path)
-            .current_dir(context.project_path.join("jumake_build"))
+            .current_dir(context.project_path.join(&context.build_dir))
             .status()?;
     }
 
@@ -88,11 +102,11 @@ fn find_executable(context: &Context) -> Result<String, Box<dyn Error>> {
     println!("Build type: {:?}", context.build_type);
     println!("Project name: {}", context.project_name);
 
-    let build_dir = context.project_path.join("jumake_build");
+    let build_dir = context.project_path.join(&context.build_dir);
     let mut paths = Vec::new();
     collect_paths_recursively(&build_dir, &mut paths)?;
 
-    if cfg!(target_os = "macos") && context.template_name.as_deref() != Some("ConsoleApp") {
+    if cfg!(target_os = "macos") && !is_console_template(context) {
         let mut app_candidates: Vec<PathBuf> = paths
             .iter()
             .filter(|p| p.extension().and_then(|ext| ext.to_str()) == Some("app"))
@@ -143,6 +157,10 @@ fn find_executable(context: &Context) -> Result<String, Box<dyn Error>> {
     Ok(executable_path.to_string_lossy().to_string())
 }
 
+fn is_console_template(context: &Context) -> bool {
+    CONSOLE_TEMPLATES.contains(&context.template_name.as_deref().unwrap_or_default())
+}
+
 fn collect_paths_recursively(dir: &Path, output: &mut Vec<PathBuf>) -> io::Result<()> {
     for entry in fs::read_dir(dir)? {
         let entry = entry?;
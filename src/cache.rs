@@ -0,0 +1,272 @@
+// src/cache.rs
+use crate::initialize_git::{fetch_revision, FetchedRevision};
+use git2::Repository;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const CACHE_DIR_ENV_VAR: &str = "JUMAKE_CACHE_DIR";
+const DEFAULT_BRANCH_KEY: &str = "HEAD";
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// One cached JUCE revision: a bare repository holding the objects of a single tag, branch or
+// commit, fetched once from `url` and shared by all projects created from it.
+pub struct CachedRevision {
+    pub path: PathBuf,
+    pub url: String,
+    pub fetched: FetchedRevision,
+    // Seconds since the UNIX epoch at which a project was last created from this revision.
+    pub last_used: u64,
+}
+
+// The per-user cache directory: JUMAKE_CACHE_DIR if set, otherwise the platform's cache
+// directory (XDG_CACHE_HOME or ~/.cache on Linux, ~/Library/Caches on macOS, %LOCALAPPDATA% on Windows).
+pub fn default_cache_dir() -> Option<PathBuf> {
+    if let Some(cache_dir) = env::var_os(CACHE_DIR_ENV_VAR) {
+        return Some(PathBuf::from(cache_dir));
+    }
+
+    let home = env::var_os("HOME").map(PathBuf::from);
+    if cfg!(target_os = "windows") {
+        env::var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join("jumake").join("cache"))
+    } else if cfg!(target_os = "macos") {
+        home.map(|home| home.join("Library").join("Caches").join("jumake"))
+    } else {
+        env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| home.map(|home| home.join(".cache")))
+            .map(|dir| dir.join("jumake"))
+    }
+}
+
+// Return the cached revision for `url` and `revision`, fetching it into the cache first if needed.
+pub fn ensure_cached(
+    cache_dir: &Path,
+    url: &str,
+    revision: Option<&str>,
+) -> Result<CachedRevision, Box<dyn Error>> {
+    let path = juce_cache_root(cache_dir)
+        .join(cache_key(url))
+        .join(format!(
+            "{}.git",
+            cache_key(revision.unwrap_or(DEFAULT_BRANCH_KEY))
+        ));
+
+    // A cache entry is only complete once its URL was recorded after a successful fetch.
+    if path.exists() && read_cached_revision(&path).is_err() {
+        fs::remove_dir_all(&path)?;
+    }
+
+    if !path.exists() {
+        println!(
+            "Caching JUCE {} from {} in {}... this may take some minutes. Please be patient!",
+            revision.unwrap_or("(default branch)"),
+            url,
+            path.display()
+        );
+        fs::create_dir_all(&path)?;
+        let fetched = Repository::init_bare(&path)
+            .map_err(Box::<dyn Error>::from)
+            .and_then(|repo| {
+                fetch_revision(&repo, url, revision)?;
+                let mut config = repo.config()?;
+                config.set_str("jumake.url", url)?;
+                if let Some(revision) = revision {
+                    config.set_str("jumake.revision", revision)?;
+                }
+                Ok(())
+            });
+        if let Err(e) = fetched {
+            let _ = fs::remove_dir_all(&path);
+            return Err(e);
+        }
+    } else if revision.is_none() || read_cached_revision(&path)?.fetched.branch.is_some() {
+        // Branches and the default branch move; only tags and commits can be reused as they are.
+        println!("Updating cached JUCE from {}...", path.display());
+        if let Err(e) = refetch(&path, url) {
+            println!(
+                "Warning: Could not update cached JUCE ({}); using the cached commit.",
+                e
+            );
+        }
+    } else {
+        println!("Using cached JUCE from {}", path.display());
+    }
+
+    let repo = Repository::open_bare(&path)?;
+    repo.config()?.set_i64("jumake.lastused", now() as i64)?;
+    read_cached_revision(&path)
+}
+
+// Hard-link (or copy, across file systems) the objects of a cached revision into the git
+// directory of a new repository, so the repository does not depend on the cache afterwards.
+pub fn copy_cached_objects(cached: &CachedRevision, git_dir: &Path) -> io::Result<()> {
+    link_or_copy_dir(&cached.path.join("objects"), &git_dir.join("objects"))?;
+
+    // Revisions fetched shallow need the shallow boundary too, or their history looks broken.
+    let shallow_path = cached.path.join("shallow");
+    if shallow_path.is_file() {
+        fs::copy(shallow_path, git_dir.join("shallow"))?;
+    }
+    Ok(())
+}
+
+pub fn list_cached_revisions(cache_dir: &Path) -> Result<Vec<CachedRevision>, Box<dyn Error>> {
+    let mut revisions = Vec::new();
+    let root = juce_cache_root(cache_dir);
+    if !root.exists() {
+        return Ok(revisions);
+    }
+
+    for url_entry in fs::read_dir(root)? {
+        let url_path = url_entry?.path();
+        if !url_path.is_dir() {
+            continue;
+        }
+        for revision_entry in fs::read_dir(url_path)? {
+            if let Ok(cached) = read_cached_revision(&revision_entry?.path()) {
+                revisions.push(cached);
+            }
+        }
+    }
+    revisions.sort_by(|a, b| (&a.url, &a.fetched.revision).cmp(&(&b.url, &b.fetched.revision)));
+    Ok(revisions)
+}
+
+// Fetch the latest commit of every cached branch. Tags and commits never change and are skipped.
+// Returns the updated revisions together with their previous state.
+pub fn update_cache(
+    cache_dir: &Path,
+) -> Result<Vec<(CachedRevision, CachedRevision)>, Box<dyn Error>> {
+    let mut updated = Vec::new();
+    for cached in list_cached_revisions(cache_dir)? {
+        if cached.fetched.branch.is_none() {
+            continue;
+        }
+        println!("Updating cached JUCE {}...", cached.fetched.revision);
+        refetch(&cached.path, &cached.url)?;
+        let current = read_cached_revision(&cached.path)?;
+        if current.fetched.commit != cached.fetched.commit {
+            updated.push((cached, current));
+        }
+    }
+    Ok(updated)
+}
+
+// Remove cached revisions that have not been used for `older_than_days` days.
+pub fn prune_cache(
+    cache_dir: &Path,
+    older_than_days: u64,
+) -> Result<Vec<CachedRevision>, Box<dyn Error>> {
+    let cutoff = now().saturating_sub(older_than_days * SECONDS_PER_DAY);
+    let mut removed = Vec::new();
+    for cached in list_cached_revisions(cache_dir)? {
+        if older_than_days > 0 && cached.last_used > cutoff {
+            continue;
+        }
+        fs::remove_dir_all(&cached.path)?;
+        if let Some(url_path) = cached.path.parent() {
+            // Only succeeds once the last revision of this URL is gone.
+            let _ = fs::remove_dir(url_path);
+        }
+        removed.push(cached);
+    }
+    Ok(removed)
+}
+
+pub fn directory_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            directory_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
+}
+
+// Fetch the recorded revision of the cache entry at `path` again.
+fn refetch(path: &Path, url: &str) -> Result<(), Box<dyn Error>> {
+    let repo = Repository::open_bare(path)?;
+    let revision = repo.config()?.get_string("jumake.revision").ok();
+    fetch_revision(&repo, url, revision.as_deref())?;
+    Ok(())
+}
+
+fn read_cached_revision(path: &Path) -> Result<CachedRevision, Box<dyn Error>> {
+    let repo = Repository::open_bare(path)?;
+    let config = repo.config()?;
+    let url = config.get_string("jumake.url")?;
+    let revision = config.get_string("jumake.revision").ok();
+    let last_used = config.get_i64("jumake.lastused").unwrap_or(0).max(0) as u64;
+
+    let head = repo.head()?;
+    let branch = if head.is_branch() {
+        head.shorthand().map(str::to_string)
+    } else {
+        None
+    };
+    let commit = head.peel_to_commit()?.id();
+
+    Ok(CachedRevision {
+        path: path.to_path_buf(),
+        url,
+        fetched: FetchedRevision {
+            revision: revision.or_else(|| branch.clone()).unwrap_or_default(),
+            branch,
+            commit,
+        },
+        last_used,
+    })
+}
+
+fn juce_cache_root(cache_dir: &Path) -> PathBuf {
+    cache_dir.join("juce")
+}
+
+// Turn a URL or revision into a readable, file-system safe directory name. Other characters
+// are escaped as `_` and their hex bytes, so different values never share a key.
+pub fn cache_key(value: &str) -> String {
+    let mut key = String::new();
+    for (i, byte) in value.bytes().enumerate() {
+        // A leading dot is escaped too, so no key is "." or "..".
+        if byte.is_ascii_alphanumeric() || (byte == b'.' && i > 0) || byte == b'-' {
+            key.push(byte as char);
+        } else {
+            key.push_str(&format!("_{:02x}", byte));
+        }
+    }
+    key
+}
+
+fn link_or_copy_dir(source: &Path, target: &Path) -> io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let source_path = entry.path();
+        let target_path = target.join(entry.file_name());
+        if source_path.is_dir() {
+            // objects/info only holds alternates and pack lists of the cache itself.
+            if entry.file_name() != "info" {
+                link_or_copy_dir(&source_path, &target_path)?;
+            }
+        } else if !target_path.exists() && fs::hard_link(&source_path, &target_path).is_err() {
+            fs::copy(&source_path, &target_path)?;
+        }
+    }
+    Ok(())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
@@ -1,9 +1,63 @@
 // src/context.rs
+use crate::placeholders::Variables;
+use crate::plugin::PluginOptions;
 use std::path::PathBuf;
 
+pub const DEFAULT_JUCE_URL: &str = "https://github.com/juce-framework/JUCE.git";
+pub const JUCE_URL_ENV_VAR: &str = "JUMAKE_JUCE_URL";
+pub const DEFAULT_BUILD_DIR: &str = "jumake_build";
+pub const DEFAULT_PROJECT_VERSION: &str = "0.0.1";
+
+#[derive(Clone)]
 pub struct Context {
     pub project_name: String,
     pub project_path: PathBuf,
     pub template_name: Option<String>,
+    // A user template directory to render instead of the built-in template files.
+    pub template_dir: Option<PathBuf>,
+    // Values for placeholders declared by a user template.
+    pub template_variables: Variables,
     pub build_type: String,
+    pub version: String,
+    pub company: Option<String>,
+    pub juce_source: String,
+    pub juce_revision: Option<String>,
+    pub juce_path: Option<PathBuf>,
+    // The shared JUCE cache to clone from; None clones straight from `juce_source`.
+    pub juce_cache_dir: Option<PathBuf>,
+    pub build_dir: String,
+    pub plugin: Option<PluginOptions>,
+    // Keep the partially created project if `create_project` fails, for debugging.
+    pub keep_on_failure: bool,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Context {
+            project_name: String::new(),
+            project_path: PathBuf::new(),
+            template_name: None,
+            template_dir: None,
+            template_variables: Variables::new(),
+            build_type: String::from("Release"),
+            version: String::from(DEFAULT_PROJECT_VERSION),
+            company: None,
+            juce_source: String::from(DEFAULT_JUCE_URL),
+            juce_revision: None,
+            juce_path: None,
+            juce_cache_dir: None,
+            build_dir: String::from(DEFAULT_BUILD_DIR),
+            plugin: None,
+            keep_on_failure: false,
+        }
+    }
+}
+
+// The URL JUCE is cloned from: the command line wins over the JUMAKE_JUCE_URL
+// environment variable, which wins over the official GitHub repository.
+pub fn resolve_juce_url(cli_url: Option<String>) -> String {
+    cli_url
+        .or_else(|| std::env::var(JUCE_URL_ENV_VAR).ok())
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| String::from(DEFAULT_JUCE_URL))
 }
@@ -1,80 +1,422 @@
 // src/create_files.rs
 use crate::context::Context;
+use crate::placeholders::{class_variables, project_variables, render, Variables};
+use crate::plugin::PluginKind;
+use crate::templates::{
+    element_search_dirs, find_user_element, UserTemplate, ELEMENT_MANIFEST_FILE_NAME,
+};
 use indoc::indoc;
+use regex::Regex;
 use std::error::Error;
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-const SOURCES_BEGIN_MARKER: &str = "# JUMAKE_SOURCES_BEGIN";
-const SOURCES_END_MARKER: &str = "# JUMAKE_SOURCES_END";
+pub const SOURCES_BEGIN_MARKER: &str = "# JUMAKE_SOURCES_BEGIN";
+pub const SOURCES_END_MARKER: &str = "# JUMAKE_SOURCES_END";
+pub const DSP_MODULE: &str = "juce::juce_dsp";
+
+// A file jumake writes, relative to the project directory.
+#[derive(Debug)]
+pub struct GeneratedFile {
+    pub path: PathBuf,
+    pub content: Vec<u8>,
+}
 
 pub fn create_source_files(context: &Context) -> Result<(), Box<dyn std::error::Error>> {
-    let src_path = context.project_path.join("src");
-    fs::create_dir(&src_path)?;
-
-    match context.template_name.as_deref() {
-        Some("GuiApplication") => {
-            // Create GUI application files
-            create_file_from_template(&src_path, "Main.cpp", MAIN_CPP_TEMPLATE)?;
-            create_file_from_template(&src_path, "MainComponent.cpp", MAIN_COMPONENT_CPP_TEMPLATE)?;
-            create_file_from_template(&src_path, "MainComponent.h", MAIN_COMPONENT_H_TEMPLATE)?;
-            create_file_from_template(&src_path, "CMakeLists.txt", GUI_APP_CMAKE_TEMPLATE)?;
-        }
-        Some("AudioPlugin") => {
-            // Create audio plugin files
-            create_file_from_template(
-                &src_path,
-                "PluginProcessor.cpp",
-                PLUGIN_PROCESSOR_CPP_TEMPLATE,
-            )?;
-            create_file_from_template(&src_path, "PluginProcessor.h", PLUGIN_PROCESSOR_H_TEMPLATE)?;
-            create_file_from_template(&src_path, "PluginEditor.cpp", PLUGIN_EDITOR_CPP_TEMPLATE)?;
-            create_file_from_template(&src_path, "PluginEditor.h", PLUGIN_EDITOR_H_TEMPLATE)?;
-            create_file_from_template(&src_path, "CMakeLists.txt", AUDIO_PLUGIN_CMAKE_TEMPLATE)?;
-        }
-        Some("ConsoleApp") => {
-            // Create console application files
-            create_file_from_template(&src_path, "Main.cpp", CONSOLE_APP_MAIN_CPP_TEMPLATE)?;
-            create_file_from_template(&src_path, "CMakeLists.txt", CONSOLE_APP_CMAKE_TEMPLATE)?;
+    write_generated_files(&context.project_path, &generate_source_files(context)?)
+}
+
+// Render the files of the project's template without writing them.
+pub fn generate_source_files(context: &Context) -> Result<Vec<GeneratedFile>, Box<dyn Error>> {
+    let variables = project_variables(context);
+    if let Some(template_dir) = &context.template_dir {
+        if context.plugin.as_ref().is_some_and(|plugin| plugin.apvts) {
+            return Err("--with-apvts only works with the built-in AudioPlugin template".into());
         }
-        _ => {
-            return Err(format!("Unknown template: {:?}", context.template_name).into());
+        return UserTemplate::load(template_dir)?.generate(&variables);
+    }
+
+    let template_name = context.template_name.as_deref().unwrap_or_default();
+    let template = find_builtin_template(template_name)
+        .ok_or(format!("Unknown template: {:?}", context.template_name))?;
+    let plugin = context.plugin.as_ref();
+    if plugin.is_some_and(|plugin| plugin.apvts) && template.name != "AudioPlugin" {
+        return Err(format!(
+            "--with-apvts does not apply to the {} template",
+            template.name
+        )
+        .into());
+    }
+    let mut files = Vec::new();
+    for (file_name, content) in template.files {
+        let content = plugin
+            .filter(|_| template.name == "AudioPlugin")
+            .and_then(|plugin| plugin_variant_source(plugin.kind, plugin.apvts, file_name))
+            .unwrap_or(content);
+        files.push(render_file(file_name, content, &variables)?);
+    }
+    Ok(files)
+}
+
+// The AudioPlugin template is a pass-through effect; the other plugin kinds replace its
+// processor and editor, and each kind has a variant with an AudioProcessorValueTreeState and
+// the JUMAKE markers of `jumake add parameter` for --with-apvts.
+const PLUGIN_VARIANT_SOURCES: [(PluginKind, bool, &str, &[u8]); 20] = [
+    (
+        PluginKind::Effect,
+        true,
+        "src/PluginProcessor.cpp",
+        PLUGIN_APVTS_PROCESSOR_CPP_TEMPLATE,
+    ),
+    (
+        PluginKind::Effect,
+        true,
+        "src/PluginProcessor.h",
+        PLUGIN_APVTS_PROCESSOR_H_TEMPLATE,
+    ),
+    (
+        PluginKind::Effect,
+        true,
+        "src/PluginEditor.cpp",
+        PLUGIN_APVTS_EDITOR_CPP_TEMPLATE,
+    ),
+    (
+        PluginKind::Effect,
+        true,
+        "src/PluginEditor.h",
+        PLUGIN_APVTS_EDITOR_H_TEMPLATE,
+    ),
+    (
+        PluginKind::Synth,
+        false,
+        "src/PluginProcessor.cpp",
+        SYNTH_PROCESSOR_CPP_TEMPLATE,
+    ),
+    (
+        PluginKind::Synth,
+        false,
+        "src/PluginProcessor.h",
+        SYNTH_PROCESSOR_H_TEMPLATE,
+    ),
+    (
+        PluginKind::Synth,
+        false,
+        "src/PluginEditor.cpp",
+        SYNTH_EDITOR_CPP_TEMPLATE,
+    ),
+    (
+        PluginKind::Synth,
+        false,
+        "src/PluginEditor.h",
+        SYNTH_EDITOR_H_TEMPLATE,
+    ),
+    (
+        PluginKind::Synth,
+        true,
+        "src/PluginProcessor.cpp",
+        SYNTH_APVTS_PROCESSOR_CPP_TEMPLATE,
+    ),
+    (
+        PluginKind::Synth,
+        true,
+        "src/PluginProcessor.h",
+        SYNTH_APVTS_PROCESSOR_H_TEMPLATE,
+    ),
+    (
+        PluginKind::Synth,
+        true,
+        "src/PluginEditor.cpp",
+        SYNTH_APVTS_EDITOR_CPP_TEMPLATE,
+    ),
+    (
+        PluginKind::Synth,
+        true,
+        "src/PluginEditor.h",
+        SYNTH_APVTS_EDITOR_H_TEMPLATE,
+    ),
+    (
+        PluginKind::MidiEffect,
+        false,
+        "src/PluginProcessor.cpp",
+        MIDI_EFFECT_PROCESSOR_CPP_TEMPLATE,
+    ),
+    (
+        PluginKind::MidiEffect,
+        false,
+        "src/PluginProcessor.h",
+        MIDI_EFFECT_PROCESSOR_H_TEMPLATE,
+    ),
+    (
+        PluginKind::MidiEffect,
+        false,
+        "src/PluginEditor.cpp",
+        MIDI_EFFECT_EDITOR_CPP_TEMPLATE,
+    ),
+    (
+        PluginKind::MidiEffect,
+        false,
+        "src/PluginEditor.h",
+        MIDI_EFFECT_EDITOR_H_TEMPLATE,
+    ),
+    (
+        PluginKind::MidiEffect,
+        true,
+        "src/PluginProcessor.cpp",
+        MIDI_EFFECT_APVTS_PROCESSOR_CPP_TEMPLATE,
+    ),
+    (
+        PluginKind::MidiEffect,
+        true,
+        "src/PluginProcessor.h",
+        MIDI_EFFECT_APVTS_PROCESSOR_H_TEMPLATE,
+    ),
+    (
+        PluginKind::MidiEffect,
+        true,
+        "src/PluginEditor.cpp",
+        MIDI_EFFECT_APVTS_EDITOR_CPP_TEMPLATE,
+    ),
+    (
+        PluginKind::MidiEffect,
+        true,
+        "src/PluginEditor.h",
+        MIDI_EFFECT_APVTS_EDITOR_H_TEMPLATE,
+    ),
+];
+
+fn plugin_variant_source(kind: PluginKind, apvts: bool, file_name: &str) -> Option<&'static [u8]> {
+    PLUGIN_VARIANT_SOURCES
+        .iter()
+        .find(|(source_kind, source_apvts, source_name, _)| {
+            *source_kind == kind && *source_apvts == apvts && *source_name == file_name
+        })
+        .map(|(_, _, _, content)| *content)
+}
+
+pub fn write_generated_files(
+    project_path: &Path,
+    files: &[GeneratedFile],
+) -> Result<(), Box<dyn Error>> {
+    for file in files {
+        let path = project_path.join(&file.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
         }
+        let mut output = File::create(&path)?;
+        output.write_all(&file.content)?;
+        println!("Created file: {}", path.display());
     }
     Ok(())
 }
 
+// A project template compiled into jumake.
+pub struct BuiltinTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    // The files of the template, relative to the project directory.
+    pub files: &'static [(&'static str, &'static [u8])],
+}
+
+impl BuiltinTemplate {
+    // The name used on the command line, e.g. "audio-plugin" for "AudioPlugin".
+    pub fn cli_name(&self) -> String {
+        let mut cli_name = String::new();
+        for (i, c) in self.name.chars().enumerate() {
+            if c.is_ascii_uppercase() && i > 0 {
+                cli_name.push('-');
+            }
+            cli_name.push(c.to_ascii_lowercase());
+        }
+        cli_name
+    }
+}
+
+pub const BUILTIN_TEMPLATES: [BuiltinTemplate; 6] = [
+    BuiltinTemplate {
+        name: "GuiApplication",
+        description: "A JUCE GUI application with a main window and component",
+        files: &[
+            ("src/Main.cpp", MAIN_CPP_TEMPLATE),
+            ("src/MainComponent.cpp", MAIN_COMPONENT_CPP_TEMPLATE),
+            ("src/MainComponent.h", MAIN_COMPONENT_H_TEMPLATE),
+            ("src/CMakeLists.txt", GUI_APP_CMAKE_TEMPLATE),
+        ],
+    },
+    BuiltinTemplate {
+        name: "AudioPlugin",
+        description: "An audio plugin with processor and editor (VST3, AU, Standalone, ...)",
+        files: &[
+            ("src/PluginProcessor.cpp", PLUGIN_PROCESSOR_CPP_TEMPLATE),
+            ("src/PluginProcessor.h", PLUGIN_PROCESSOR_H_TEMPLATE),
+            ("src/PluginEditor.cpp", PLUGIN_EDITOR_CPP_TEMPLATE),
+            ("src/PluginEditor.h", PLUGIN_EDITOR_H_TEMPLATE),
+            ("src/CMakeLists.txt", AUDIO_PLUGIN_CMAKE_TEMPLATE),
+        ],
+    },
+    BuiltinTemplate {
+        name: "ConsoleApp",
+        description: "A command-line application using juce_core",
+        files: &[
+            ("src/Main.cpp", CONSOLE_APP_MAIN_CPP_TEMPLATE),
+            ("src/CMakeLists.txt", CONSOLE_APP_CMAKE_TEMPLATE),
+        ],
+    },
+    BuiltinTemplate {
+        name: "StaticLibrary",
+        description: "A static library linked against JUCE modules, to share code between projects",
+        files: &[
+            ("src/Library.cpp", STATIC_LIBRARY_CPP_TEMPLATE),
+            ("src/Library.h", STATIC_LIBRARY_H_TEMPLATE),
+            ("src/CMakeLists.txt", STATIC_LIBRARY_CMAKE_TEMPLATE),
+        ],
+    },
+    BuiltinTemplate {
+        name: "JuceModule",
+        description: "A custom JUCE module plus a console app to try it out",
+        files: &[
+            ("src/{{namespace}}/{{namespace}}.h", JUCE_MODULE_H_TEMPLATE),
+            (
+                "src/{{namespace}}/{{namespace}}.cpp",
+                JUCE_MODULE_CPP_TEMPLATE,
+            ),
+            ("src/Main.cpp", JUCE_MODULE_MAIN_CPP_TEMPLATE),
+            ("src/CMakeLists.txt", JUCE_MODULE_CMAKE_TEMPLATE),
+        ],
+    },
+    BuiltinTemplate {
+        name: "PluginHost",
+        description: "A GUI app that scans for, loads and plays audio plugins",
+        files: &[
+            ("src/Main.cpp", MAIN_CPP_TEMPLATE),
+            (
+                "src/MainComponent.cpp",
+                PLUGIN_HOST_MAIN_COMPONENT_CPP_TEMPLATE,
+            ),
+            ("src/MainComponent.h", PLUGIN_HOST_MAIN_COMPONENT_H_TEMPLATE),
+            ("src/CMakeLists.txt", PLUGIN_HOST_CMAKE_TEMPLATE),
+        ],
+    },
+];
+
+// Look up a built-in template by its name, e.g. "AudioPlugin" or "audio-plugin".
+pub fn find_builtin_template(name: &str) -> Option<&'static BuiltinTemplate> {
+    let normalize = |name: &str| name.replace('-', "").to_ascii_lowercase();
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|template| normalize(template.name) == normalize(name))
+}
+
+// What `jumake add` changes: the new files and the updated src/CMakeLists.txt.
+pub struct ClassChanges {
+    pub class_name: String,
+    pub files: Vec<GeneratedFile>,
+    // Relative to the project directory.
+    pub cmakelists_path: PathBuf,
+    pub old_cmakelists: String,
+    pub new_cmakelists: String,
+    pub warnings: Vec<&'static str>,
+}
+
 // Function to add a class or component file to a project based on the given context and element type.
 pub fn add_class(
     context: &Context,
     element_type: &str,
     element_name: &str,
+    options: &ClassOptions,
 ) -> Result<(), Box<dyn Error>> {
+    let changes = plan_class(context, element_type, element_name, options)?;
+    write_generated_files(&context.project_path, &changes.files)?;
+    if changes.new_cmakelists != changes.old_cmakelists {
+        fs::write(
+            context.project_path.join(&changes.cmakelists_path),
+            &changes.new_cmakelists,
+        )?;
+    }
+    for warning in &changes.warnings {
+        println!("{}", warning);
+    }
+
+    // Confirm the addition of the new class or component.
+    println!(
+        "{} '{}' added successfully!",
+        element_type, changes.class_name
+    );
+    Ok(())
+}
+
+// Options of `jumake add` for classes.
+#[derive(Debug, Clone, Default)]
+pub struct ClassOptions {
+    // The namespace the class is declared in, e.g. "mylib::dsp".
+    pub namespace: Option<String>,
+    // Generate only the header, with inline definitions.
+    pub header_only: bool,
+    // Generate only the source, holding the class declaration as well.
+    pub source_only: bool,
+    // List the header in the managed source block too, so IDE projects show it.
+    pub list_headers: bool,
+}
+
+// Work out the files and CMakeLists.txt edit of `add_class` without touching the project.
+pub fn plan_class(
+    context: &Context,
+    element_type: &str,
+    element_name: &str,
+    options: &ClassOptions,
+) -> Result<ClassChanges, Box<dyn Error>> {
     // Construct the source directory path from the context's project path.
     let src_path = context.project_path.join("src");
 
+    // A name such as "dsp/filters/Biquad" puts the class into a sub-directory of src.
+    let (sub_dir, element_name) = match element_name.rsplit_once('/') {
+        Some((sub_dir, name)) => (format!("{}/", sub_dir), name),
+        None => (String::new(), element_name),
+    };
+    if !sub_dir
+        .split_terminator('/')
+        .all(|dir| dir != "." && dir != ".." && is_file_name(dir))
+    {
+        return Err(format!("Invalid directory '{}' for the class", sub_dir).into());
+    }
+    if !is_identifier(element_name) {
+        return Err(format!("'{}' is not a valid C++ class name", element_name).into());
+    }
+    if options.header_only && options.source_only {
+        return Err("--header-only and --source-only cannot be combined".into());
+    }
+    if options.list_headers && options.source_only {
+        return Err("--list-headers needs a header, but --source-only generates none".into());
+    }
+    if let Some(namespace) = &options.namespace {
+        if !namespace.split("::").all(is_identifier) {
+            return Err(format!("'{}' is not a valid C++ namespace", namespace).into());
+        }
+    }
+
     // Determine the correct templates for the header and source files based on the element type.
+    let element = element_templates(context, element_type)?;
+
+    // Append the suffix (e.g. "Component") to differentiate the element from regular classes,
+    // unless the element type accepts names that already end with it.
     let mut adjusted_element_name = element_name.to_string();
-    let (header_template, cpp_template) = match element_type {
-        "class" => (CLASS_H_TEMPLATE, CLASS_CPP_TEMPLATE),
-        "component" => {
-            // Append "Component" to the name for component types to differentiate from regular classes.
-            adjusted_element_name.push_str("Component");
-            (COMPONENT_H_TEMPLATE, COMPONENT_CPP_TEMPLATE)
-        }
-        _ => return Err(format!("Invalid element type: {}", element_type).into()),
-    };
+    if !(element.dedupe_suffix && adjusted_element_name.ends_with(&element.suffix)) {
+        adjusted_element_name.push_str(&element.suffix);
+    }
 
-    // Construct file names for the header and source files.
-    let header_file_name = format!("{}.h", adjusted_element_name);
-    let cpp_file_name = format!("{}.cpp", adjusted_element_name);
+    // Construct file names for the header and source files, relative to src.
+    let header_file_name = format!("{}{}.h", sub_dir, adjusted_element_name);
+    let cpp_file_name = format!("{}{}.cpp", sub_dir, adjusted_element_name);
 
     // Check if the header or source file already exists
     let header_path = src_path.join(&header_file_name);
     let cpp_path = src_path.join(&cpp_file_name);
 
-    if header_path.exists() || cpp_path.exists() {
+    // Tests mirror the class's directory below src, so same-named classes don't collide.
+    let test_file_name = format!("tests/{}{}Test.cpp", sub_dir, adjusted_element_name);
+    let test_exists = element.test.is_some() && context.project_path.join(&test_file_name).exists();
+
+    if header_path.exists() || cpp_path.exists() || test_exists {
         return Err(format!(
             "{} '{}' already exists in the project.",
             element_type, adjusted_element_name
@@ -82,58 +424,138 @@ pub fn add_class(
         .into());
     }
 
-    // Create files from templates with the adjusted names.
-    create_classfile_from_template(
-        &src_path,
-        &header_file_name,
-        header_template,
-        &adjusted_element_name,
+    // Render the files from templates with the adjusted names.
+    let mut variables = class_variables(context, &adjusted_element_name);
+    variables.insert("header_path".into(), header_file_name.clone());
+    let header = render_file(
+        &format!("src/{}", header_file_name),
+        &element.header,
+        &variables,
     )?;
-    create_classfile_from_template(
-        &src_path,
-        &cpp_file_name,
-        cpp_template,
-        &adjusted_element_name,
+    let source = render_file(
+        &format!("src/{}", cpp_file_name),
+        &element.source,
+        &variables,
     )?;
+    let text = |file: &GeneratedFile| String::from_utf8_lossy(&file.content).to_string();
+    let mut files = if options.header_only {
+        let content = merge_into_header(&text(&header), &text(&source), &adjusted_element_name);
+        vec![GeneratedFile {
+            content: content.into_bytes(),
+            ..header
+        }]
+    } else if options.source_only {
+        let content = merge_into_source(&text(&header), &text(&source), &adjusted_element_name);
+        vec![GeneratedFile {
+            content: content.into_bytes(),
+            ..source
+        }]
+    } else {
+        vec![header, source]
+    };
+    if let Some(namespace) = &options.namespace {
+        for file in &mut files {
+            file.content = wrap_in_namespace(&text(file), namespace).into_bytes();
+        }
+    }
+    if let Some(test) = &element.test {
+        files.push(render_file(&test_file_name, test, &variables)?);
+    }
 
-    // Add the newly created cpp file to CMakeLists.txt.
-    let cmakelists_path = src_path.join("CMakeLists.txt");
-    add_source_to_cmakelists(&cmakelists_path, &cpp_file_name)?;
+    // Add the new cpp file to CMakeLists.txt, and the header if it is listed too.
+    let cmakelists_path = PathBuf::from("src").join("CMakeLists.txt");
+    let old_cmakelists = fs::read_to_string(context.project_path.join(&cmakelists_path))?;
+    let mut new_cmakelists = old_cmakelists.clone();
+    let mut warnings: Vec<&'static str> = Vec::new();
+    let listed_files = [
+        (options.list_headers, &header_file_name),
+        (!options.header_only, &cpp_file_name),
+    ];
+    if element.test.is_some() {
+        warnings.push(
+            "Warning: The generated test is not part of any CMake target; add it to your test target.",
+        );
+    }
+    for (_, file_name) in listed_files.iter().filter(|(listed, _)| *listed) {
+        let (updated, warning) = add_source_to_cmakelists(&new_cmakelists, file_name);
+        new_cmakelists = updated;
+        if let Some(warning) = warning.filter(|warning| !warnings.contains(warning)) {
+            warnings.push(warning);
+        }
+    }
 
-    // Confirm the addition of the new class or component.
-    println!(
-        "{} '{}' added successfully!",
-        element_type, adjusted_element_name
-    );
-    Ok(())
+    // Link the JUCE modules the element needs unless the project already gets them.
+    for module in &element.modules {
+        if links_module(&new_cmakelists, module) {
+            continue;
+        }
+        let target = link_target(&new_cmakelists).ok_or_else(|| {
+            format!(
+                "Cannot link {}: no juce_add_* call in src/CMakeLists.txt names the target",
+                module
+            )
+        })?;
+        new_cmakelists = match add_link_library(&new_cmakelists, &target, module) {
+            Some(updated) => updated,
+            None => {
+                warnings.push(
+                    "Warning: Could not find target_link_libraries block; appended one for the required JUCE module.",
+                );
+                format!(
+                    "{}\ntarget_link_libraries({}\n    PRIVATE\n        {})\n",
+                    new_cmakelists, target, module
+                )
+            }
+        };
+    }
+
+    Ok(ClassChanges {
+        class_name: adjusted_element_name,
+        files,
+        cmakelists_path,
+        old_cmakelists,
+        new_cmakelists,
+        warnings,
+    })
 }
 
-fn add_source_to_cmakelists(
-    cmakelists_path: &Path,
-    cpp_file_name: &str,
-) -> Result<(), Box<dyn Error>> {
-    let content = fs::read_to_string(cmakelists_path)?;
-    if content.contains(cpp_file_name) {
-        return Ok(());
+// Insert `cpp_file_name` into the sources of a CMakeLists.txt; returns the new content and a
+// warning if the JUMAKE markers were missing.
+fn add_source_to_cmakelists(content: &str, cpp_file_name: &str) -> (String, Option<&'static str>) {
+    // Compare whole entries so that e.g. dsp/Biquad.cpp does not count as Biquad.cpp.
+    if content
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .any(|word| word == cpp_file_name)
+    {
+        return (content.to_string(), None);
     }
 
-    if let Some(updated) = insert_using_markers(&content, cpp_file_name) {
-        fs::write(cmakelists_path, updated)?;
-        return Ok(());
+    // The insertion helpers work line by line; keep the file's final newline.
+    let keep_newline = |mut updated: String| {
+        if content.ends_with('\n') && !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated
+    };
+
+    if let Some(updated) = insert_using_markers(content, cpp_file_name) {
+        return (keep_newline(updated), None);
     }
 
-    if let Some(updated) = insert_into_target_sources_block(&content, cpp_file_name) {
-        fs::write(cmakelists_path, updated)?;
-        println!("Warning: CMake markers not found; used fallback parsing for source insertion.");
-        return Ok(());
+    if let Some(updated) = insert_into_target_sources_block(content, cpp_file_name) {
+        return (
+            keep_newline(updated),
+            Some("Warning: CMake markers not found; used fallback parsing for source insertion."),
+        );
     }
 
     let appended = format!(
         "{content}\n\n# JUMAKE managed sources\ntarget_sources(${{PROJECT_NAME}}\n    PRIVATE\n        {cpp_file_name}\n)\n"
     );
-    fs::write(cmakelists_path, appended)?;
-    println!("Warning: Could not find target_sources block; appended a new JUMAKE managed block.");
-    Ok(())
+    (
+        appended,
+        Some("Warning: Could not find target_sources block; appended a new JUMAKE managed block."),
+    )
 }
 
 fn insert_using_markers(content: &str, cpp_file_name: &str) -> Option<String> {
@@ -230,6 +652,481 @@ fn insert_into_target_sources_block(content: &str, cpp_file_name: &str) -> Optio
     Some(lines.join("\n"))
 }
 
+// Split `content` into its leading preprocessor and blank lines and the rest.
+fn split_preamble(content: &str) -> (String, String) {
+    let lines: Vec<&str> = content.lines().collect();
+    let body_start = lines
+        .iter()
+        .position(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .unwrap_or(lines.len());
+    (
+        lines[..body_start].join("\n").trim_end().to_string(),
+        lines[body_start..].join("\n").trim_end().to_string(),
+    )
+}
+
+// Join a preamble and the parts of a body with blank lines.
+fn join_sections(preamble: &str, body: &[&str]) -> String {
+    let mut joined = String::new();
+    if !preamble.is_empty() {
+        joined.push_str(preamble);
+        joined.push_str("\n\n");
+    }
+    joined.push_str(&body.join("\n\n"));
+    joined.push('\n');
+    joined
+}
+
+// The preprocessor lines of `preamble` that are missing from `existing`, without the include
+// of the class's own header.
+fn extra_directives(preamble: &str, existing: &str, class_name: &str) -> Vec<String> {
+    let own_header = format!("{}.h\"", class_name);
+    preamble
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && *line != "#pragma once")
+        .filter(|line| {
+            !(line.starts_with("#include")
+                && (line.contains(&format!("\"{}", own_header))
+                    || line.contains(&format!("/{}", own_header))))
+        })
+        .filter(|line| !existing.lines().any(|existing| existing.trim() == *line))
+        .map(str::to_string)
+        .collect()
+}
+
+// A header-only class: the header followed by the definitions of the source, marked inline.
+fn merge_into_header(header: &str, source: &str, class_name: &str) -> String {
+    let (header_preamble, header_body) = split_preamble(header);
+    let (source_preamble, source_body) = split_preamble(source);
+    let mut preamble = header_preamble.clone();
+    for directive in extra_directives(&source_preamble, &header_preamble, class_name) {
+        preamble.push('\n');
+        preamble.push_str(&directive);
+    }
+
+    // Out-of-class definitions start at the beginning of a line, e.g. `void Gain::reset()`.
+    let qualifier = format!("{}::", class_name);
+    let definitions = source_body
+        .lines()
+        .map(|line| {
+            if !line.starts_with(char::is_whitespace)
+                && line.contains(&qualifier)
+                && !line.trim_end().ends_with(';')
+            {
+                format!("inline {}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    join_sections(&preamble, &[&header_body, &definitions])
+}
+
+// A source-only class: the declaration of the header followed by the definitions of the source.
+fn merge_into_source(header: &str, source: &str, class_name: &str) -> String {
+    let (header_preamble, header_body) = split_preamble(header);
+    let (source_preamble, source_body) = split_preamble(source);
+    let mut directives = extra_directives(&source_preamble, "", class_name);
+    directives.extend(extra_directives(
+        &header_preamble,
+        &directives.join("\n"),
+        class_name,
+    ));
+    join_sections(&directives.join("\n"), &[&header_body, &source_body])
+}
+
+// Wrap everything after the leading preprocessor lines of `content` in the nested namespaces
+// of `namespace`, e.g. "mylib::dsp".
+fn wrap_in_namespace(content: &str, namespace: &str) -> String {
+    let (preamble, body) = split_preamble(content);
+    let parts: Vec<&str> = namespace.split("::").collect();
+
+    let mut wrapped = String::new();
+    for part in &parts {
+        wrapped.push_str(&format!("namespace {}\n{{\n", part));
+    }
+    wrapped.push_str(&format!("\n{}\n\n", body));
+    for part in parts.iter().rev() {
+        wrapped.push_str(&format!("}} // namespace {}\n", part));
+    }
+    join_sections(&preamble, &[wrapped.trim_end()])
+}
+
+// Whether `name` can be used as a C++ identifier, i.e. is not empty, has only letters, digits
+// and '_', doesn't start with a digit and isn't a keyword.
+pub fn is_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !CPP_KEYWORDS.contains(&name)
+}
+
+// The C++20 keywords and alternative operator tokens.
+const CPP_KEYWORDS: [&str; 92] = [
+    "alignas",
+    "alignof",
+    "and",
+    "and_eq",
+    "asm",
+    "auto",
+    "bitand",
+    "bitor",
+    "bool",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "char8_t",
+    "char16_t",
+    "char32_t",
+    "class",
+    "compl",
+    "concept",
+    "const",
+    "consteval",
+    "constexpr",
+    "constinit",
+    "const_cast",
+    "continue",
+    "co_await",
+    "co_return",
+    "co_yield",
+    "decltype",
+    "default",
+    "delete",
+    "do",
+    "double",
+    "dynamic_cast",
+    "else",
+    "enum",
+    "explicit",
+    "export",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "friend",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "mutable",
+    "namespace",
+    "new",
+    "noexcept",
+    "not",
+    "not_eq",
+    "nullptr",
+    "operator",
+    "or",
+    "or_eq",
+    "private",
+    "protected",
+    "public",
+    "register",
+    "reinterpret_cast",
+    "requires",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "static_assert",
+    "static_cast",
+    "struct",
+    "switch",
+    "template",
+    "this",
+    "thread_local",
+    "throw",
+    "true",
+    "try",
+    "typedef",
+    "typeid",
+    "typename",
+    "union",
+    "unsigned",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "wchar_t",
+    "while",
+    "xor",
+    "xor_eq",
+];
+
+fn is_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+// The templates of a built-in element type, or of the user element named `element_type`.
+struct ElementTemplates {
+    suffix: String,
+    dedupe_suffix: bool,
+    header: Vec<u8>,
+    source: Vec<u8>,
+    test: Option<Vec<u8>>,
+    modules: Vec<String>,
+}
+
+fn element_templates(
+    context: &Context,
+    element_type: &str,
+) -> Result<ElementTemplates, Box<dyn Error>> {
+    if let Some(element) = CLASS_ELEMENTS
+        .iter()
+        .find(|element| element.element_type == element_type)
+    {
+        return Ok(ElementTemplates {
+            suffix: element.suffix.to_string(),
+            dedupe_suffix: element.dedupe_suffix,
+            header: element.header.to_vec(),
+            source: element.source.to_vec(),
+            test: None,
+            modules: element.module.into_iter().map(String::from).collect(),
+        });
+    }
+
+    let search_dirs = element_search_dirs(&context.project_path);
+    let element = find_user_element(&search_dirs, element_type)?.ok_or_else(|| {
+        format!(
+            "Invalid element type: {} (no built-in type or {} of that name in {})",
+            element_type,
+            ELEMENT_MANIFEST_FILE_NAME,
+            search_dirs
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    })?;
+    let files = &element.manifest.files;
+    let read = |file: &PathBuf| fs::read(element.path.join(file));
+    Ok(ElementTemplates {
+        suffix: element.manifest.element.suffix.clone(),
+        dedupe_suffix: true,
+        header: read(&files.header)?,
+        source: read(&files.source)?,
+        test: files.test.as_ref().map(read).transpose()?,
+        modules: element.manifest.element.modules.clone(),
+    })
+}
+
+// JUCE modules and the linked modules that already pull them in as dependencies.
+const MODULE_DEPENDENTS: [(&str, &[&str]); 4] = [
+    (
+        "juce::juce_core",
+        &[
+            "juce::juce_events",
+            "juce::juce_gui_basics",
+            "juce::juce_gui_extra",
+            "juce::juce_audio_basics",
+            "juce::juce_audio_processors",
+            "juce::juce_audio_utils",
+            "juce::juce_dsp",
+        ],
+    ),
+    (
+        "juce::juce_gui_basics",
+        &[
+            "juce::juce_gui_extra",
+            "juce::juce_audio_processors",
+            "juce::juce_audio_utils",
+        ],
+    ),
+    ("juce::juce_audio_processors", &["juce::juce_audio_utils"]),
+    ("juce::juce_dsp", &[]),
+];
+
+// Whether the CMakeLists content links `module`, either directly or through a module depending on it.
+pub fn links_module(content: &str, module: &str) -> bool {
+    let dependents = MODULE_DEPENDENTS
+        .iter()
+        .find(|(name, _)| *name == module)
+        .map_or(&[][..], |(_, dependents)| *dependents);
+    content
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .any(|word| word == module || dependents.contains(&word))
+}
+
+// The target the project's sources are built into, named by its juce_add_gui_app,
+// juce_add_plugin or juce_add_console_app call, or else by add_library/add_executable.
+pub fn link_target(content: &str) -> Option<String> {
+    [
+        r"(?m)^\s*juce_add_(?:gui_app|plugin|console_app)\s*\(\s*([^\s)]+)",
+        r"(?m)^\s*add_(?:library|executable)\s*\(\s*([^\s)]+)",
+    ]
+    .iter()
+    .find_map(|pattern| Regex::new(pattern).unwrap().captures(content))
+    .map(|captures| captures[1].to_string())
+}
+
+// Add `library` to the PRIVATE libraries of the first target_link_libraries(<target> ...) call.
+// Returns None if there is no such call with PRIVATE on a line of its own.
+pub fn add_link_library(content: &str, target: &str, library: &str) -> Option<String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let call = format!("target_link_libraries({}", target);
+    let target_idx = lines.iter().position(|line| {
+        line.trim_start().strip_prefix(&call).is_some_and(|rest| {
+            rest.is_empty() || rest.starts_with(|c: char| c.is_whitespace() || c == ')')
+        })
+    })?;
+
+    let mut depth = paren_delta(&lines[target_idx]);
+    let mut private_idx = None;
+    let mut block_end = target_idx;
+    for (idx, line) in lines.iter().enumerate().skip(target_idx + 1) {
+        if depth <= 0 {
+            break;
+        }
+        depth += paren_delta(line);
+        block_end = idx;
+        if private_idx.is_none() && line.trim() == "PRIVATE" {
+            private_idx = Some(idx);
+        }
+    }
+    let private_idx = private_idx?;
+    let block = &lines[target_idx..=block_end];
+    if block.iter().any(|line| {
+        line.split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+            .any(|word| word == library)
+    }) {
+        return Some(content.to_string());
+    }
+
+    // The last library of the PRIVATE section, skipping comments.
+    let mut last_idx = private_idx;
+    for (idx, line) in lines
+        .iter()
+        .enumerate()
+        .take(block_end + 1)
+        .skip(private_idx + 1)
+    {
+        let trimmed = line.trim_start();
+        if ["PUBLIC", "INTERFACE", "PRIVATE", ")"]
+            .iter()
+            .any(|keyword| trimmed.starts_with(keyword))
+        {
+            break;
+        }
+        if !trimmed.is_empty() && !trimmed.starts_with('#') {
+            last_idx = idx;
+        }
+    }
+    let library_indent = if last_idx > private_idx {
+        leading_spaces(&lines[last_idx])
+    } else {
+        leading_spaces(&lines[private_idx]) + 4
+    };
+    let mut new_line = format!("{:indent$}{}", "", library, indent = library_indent);
+    // A closing parenthesis right after the last library moves to the new one.
+    if last_idx == block_end && lines[last_idx].trim_end().ends_with(')') {
+        let trimmed = lines[last_idx].trim_end();
+        lines[last_idx] = trimmed[..trimmed.len() - 1].to_string();
+        new_line.push(')');
+    }
+    lines.insert(last_idx + 1, new_line);
+
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Some(updated)
+}
+
+// Wrap the PRIVATE sources of the first target_sources() block in the JUMAKE markers, so that
+// `jumake add` can maintain them. Returns None if the markers already exist or the block has no
+// PRIVATE keyword on a line of its own.
+pub fn add_source_markers(content: &str) -> Option<String> {
+    if content.contains(SOURCES_BEGIN_MARKER) {
+        return None;
+    }
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let target_idx = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with("target_sources("))?;
+
+    let mut depth = paren_delta(&lines[target_idx]);
+    let mut i = target_idx + 1;
+    let mut private_idx = None;
+    let mut block_end = None;
+    while depth > 0 && i < lines.len() {
+        depth += paren_delta(&lines[i]);
+        if private_idx.is_none() && lines[i].trim() == "PRIVATE" {
+            private_idx = Some(i);
+        }
+        if depth <= 0 {
+            block_end = Some(i);
+        }
+        i += 1;
+    }
+    let (private_idx, block_end) = (private_idx?, block_end?);
+
+    let mut end_idx = private_idx + 1;
+    while end_idx <= block_end {
+        let trimmed = lines[end_idx].trim_start();
+        if trimmed.starts_with(')')
+            || trimmed.starts_with("PUBLIC")
+            || trimmed.starts_with("INTERFACE")
+            || trimmed.starts_with("PRIVATE")
+        {
+            break;
+        }
+        end_idx += 1;
+    }
+    let source_indent = if end_idx > private_idx + 1 {
+        leading_spaces(&lines[private_idx + 1])
+    } else {
+        leading_spaces(&lines[private_idx]) + 4
+    };
+
+    if end_idx > block_end {
+        // The last source closes the block (`Main.cpp)`); move the parenthesis to its own line.
+        let last = lines[block_end].clone();
+        let close = last.rfind(')')?;
+        lines[block_end] = last[..close].trim_end().to_string();
+        lines.insert(
+            block_end + 1,
+            format!(
+                "{:indent$})",
+                "",
+                indent = leading_spaces(&lines[target_idx])
+            ),
+        );
+        end_idx = block_end + 1;
+    }
+
+    lines.insert(
+        end_idx,
+        format!(
+            "{:indent$}{}",
+            "",
+            SOURCES_END_MARKER,
+            indent = source_indent
+        ),
+    );
+    lines.insert(
+        private_idx + 1,
+        format!(
+            "{:indent$}{}",
+            "",
+            SOURCES_BEGIN_MARKER,
+            indent = source_indent
+        ),
+    );
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Some(updated)
+}
+
 fn paren_delta(line: &str) -> i32 {
     let opens = line.chars().filter(|&c| c == '(').count() as i32;
     let closes = line.chars().filter(|&c| c == ')').count() as i32;
@@ -240,29 +1137,20 @@ fn leading_spaces(line: &str) -> usize {
     line.chars().take_while(|c| c.is_whitespace()).count()
 }
 
-fn create_classfile_from_template(
-    src_path: &Path,
+// Render a template's placeholders into the file at `file_name`, which may use them as well.
+pub fn render_file(
     file_name: &str,
     template: &[u8],
-    element_name: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let mut file = File::create(src_path.join(file_name))?;
-    let content = String::from_utf8_lossy(template).replace("Template", element_name);
-    file.write_all(content.as_bytes())?;
-    println!("Created file: {}", src_path.join(file_name).display());
-    Ok(())
+    variables: &Variables,
+) -> Result<GeneratedFile, String> {
+    let path = render(file_name, variables, file_name)?;
+    let content = render(&String::from_utf8_lossy(template), variables, file_name)?;
+    Ok(GeneratedFile {
+        path: PathBuf::from(path),
+        content: content.into_bytes(),
+    })
 }
 
-fn create_file_from_template(
-    src_path: &Path,
-    file_name: &str,
-    template: &[u8],
-) -> Result<(), Box<dyn std::error::Error>> {
-    let mut file = File::create(src_path.join(file_name))?;
-    file.write_all(template)?;
-    println!("Created file: {}", src_path.join(file_name).display());
-    Ok(())
-}
 // Define the templates as byte slices
 const MAIN_CPP_TEMPLATE: &[u8] =
     include_bytes!("../templates/GuiApplicationTemplate/Main.cpp.template");
@@ -283,6 +1171,48 @@ const PLUGIN_EDITOR_H_TEMPLATE: &[u8] =
     include_bytes!("../templates/AudioPluginTemplate/PluginEditor.h.template");
 const AUDIO_PLUGIN_CMAKE_TEMPLATE: &[u8] =
     include_bytes!("../templates/AudioPluginTemplate/CMakeLists.txt.template");
+const SYNTH_PROCESSOR_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/Synth/PluginProcessor.cpp.template");
+const SYNTH_PROCESSOR_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/Synth/PluginProcessor.h.template");
+const SYNTH_EDITOR_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/Synth/PluginEditor.cpp.template");
+const SYNTH_EDITOR_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/Synth/PluginEditor.h.template");
+const MIDI_EFFECT_PROCESSOR_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/MidiEffect/PluginProcessor.cpp.template");
+const MIDI_EFFECT_PROCESSOR_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/MidiEffect/PluginProcessor.h.template");
+const MIDI_EFFECT_EDITOR_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/MidiEffect/PluginEditor.cpp.template");
+const MIDI_EFFECT_EDITOR_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/MidiEffect/PluginEditor.h.template");
+
+const PLUGIN_APVTS_PROCESSOR_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/Apvts/PluginProcessor.cpp.template");
+const PLUGIN_APVTS_PROCESSOR_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/Apvts/PluginProcessor.h.template");
+const PLUGIN_APVTS_EDITOR_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/Apvts/PluginEditor.cpp.template");
+const PLUGIN_APVTS_EDITOR_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/Apvts/PluginEditor.h.template");
+const SYNTH_APVTS_PROCESSOR_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/Synth/Apvts/PluginProcessor.cpp.template");
+const SYNTH_APVTS_PROCESSOR_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/Synth/Apvts/PluginProcessor.h.template");
+const SYNTH_APVTS_EDITOR_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/Synth/Apvts/PluginEditor.cpp.template");
+const SYNTH_APVTS_EDITOR_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/Synth/Apvts/PluginEditor.h.template");
+const MIDI_EFFECT_APVTS_PROCESSOR_CPP_TEMPLATE: &[u8] = include_bytes!(
+    "../templates/AudioPluginTemplate/MidiEffect/Apvts/PluginProcessor.cpp.template"
+);
+const MIDI_EFFECT_APVTS_PROCESSOR_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/MidiEffect/Apvts/PluginProcessor.h.template");
+const MIDI_EFFECT_APVTS_EDITOR_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/MidiEffect/Apvts/PluginEditor.cpp.template");
+const MIDI_EFFECT_APVTS_EDITOR_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/AudioPluginTemplate/MidiEffect/Apvts/PluginEditor.h.template");
 
 const CONSOLE_APP_CMAKE_TEMPLATE: &[u8] =
     include_bytes!("../templates/ConsoleAppTemplate/CMakeLists.txt.template");
@@ -290,27 +1220,169 @@ const CONSOLE_APP_CMAKE_TEMPLATE: &[u8] =
 const CONSOLE_APP_MAIN_CPP_TEMPLATE: &[u8] =
     include_bytes!("../templates/ConsoleAppTemplate/Main.cpp.template");
 
+const STATIC_LIBRARY_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/StaticLibraryTemplate/Library.cpp.template");
+const STATIC_LIBRARY_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/StaticLibraryTemplate/Library.h.template");
+const STATIC_LIBRARY_CMAKE_TEMPLATE: &[u8] =
+    include_bytes!("../templates/StaticLibraryTemplate/CMakeLists.txt.template");
+
+const JUCE_MODULE_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/JuceModuleTemplate/Module.h.template");
+const JUCE_MODULE_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/JuceModuleTemplate/Module.cpp.template");
+const JUCE_MODULE_MAIN_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/JuceModuleTemplate/Main.cpp.template");
+const JUCE_MODULE_CMAKE_TEMPLATE: &[u8] =
+    include_bytes!("../templates/JuceModuleTemplate/CMakeLists.txt.template");
+
+const PLUGIN_HOST_MAIN_COMPONENT_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/PluginHostTemplate/MainComponent.cpp.template");
+const PLUGIN_HOST_MAIN_COMPONENT_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/PluginHostTemplate/MainComponent.h.template");
+const PLUGIN_HOST_CMAKE_TEMPLATE: &[u8] =
+    include_bytes!("../templates/PluginHostTemplate/CMakeLists.txt.template");
+
 const CLASS_H_TEMPLATE: &[u8] = include_bytes!("../templates/ClassTemplates/Class.h.template");
 const CLASS_CPP_TEMPLATE: &[u8] = include_bytes!("../templates/ClassTemplates/Class.cpp.template");
+const PROCESSOR_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/ClassTemplates/Processor.h.template");
+const PROCESSOR_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/ClassTemplates/Processor.cpp.template");
+const LOOK_AND_FEEL_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/ClassTemplates/LookAndFeel.h.template");
+const LOOK_AND_FEEL_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/ClassTemplates/LookAndFeel.cpp.template");
+const THREAD_H_TEMPLATE: &[u8] = include_bytes!("../templates/ClassTemplates/Thread.h.template");
+const THREAD_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/ClassTemplates/Thread.cpp.template");
+const TIMER_COMPONENT_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/ClassTemplates/TimerComponent.h.template");
+const TIMER_COMPONENT_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/ClassTemplates/TimerComponent.cpp.template");
+const AUDIO_APP_COMPONENT_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/ClassTemplates/AudioAppComponent.h.template");
+const AUDIO_APP_COMPONENT_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/ClassTemplates/AudioAppComponent.cpp.template");
+const AUDIO_PROCESSOR_H_TEMPLATE: &[u8] =
+    include_bytes!("../templates/ClassTemplates/AudioProcessor.h.template");
+const AUDIO_PROCESSOR_CPP_TEMPLATE: &[u8] =
+    include_bytes!("../templates/ClassTemplates/AudioProcessor.cpp.template");
 const COMPONENT_H_TEMPLATE: &[u8] =
     include_bytes!("../templates/ClassTemplates/Component.h.template");
 const COMPONENT_CPP_TEMPLATE: &[u8] =
     include_bytes!("../templates/ClassTemplates/Component.cpp.template");
 
+// An element type of `jumake add`: its templates, the suffix appended to the class name and
+// the JUCE module the generated class needs.
+struct ClassElement {
+    element_type: &'static str,
+    suffix: &'static str,
+    // Whether a name already ending with the suffix is used as is. Components always get it,
+    // so `add component MainComponent` doesn't clash with the template's MainComponent.
+    dedupe_suffix: bool,
+    header: &'static [u8],
+    source: &'static [u8],
+    module: Option<&'static str>,
+}
+
+const CLASS_ELEMENTS: [ClassElement; 8] = [
+    ClassElement {
+        element_type: "class",
+        suffix: "",
+        dedupe_suffix: false,
+        header: CLASS_H_TEMPLATE,
+        source: CLASS_CPP_TEMPLATE,
+        module: None,
+    },
+    ClassElement {
+        element_type: "component",
+        suffix: "Component",
+        dedupe_suffix: false,
+        header: COMPONENT_H_TEMPLATE,
+        source: COMPONENT_CPP_TEMPLATE,
+        module: None,
+    },
+    ClassElement {
+        element_type: "processor",
+        suffix: "Processor",
+        dedupe_suffix: true,
+        header: PROCESSOR_H_TEMPLATE,
+        source: PROCESSOR_CPP_TEMPLATE,
+        module: Some(DSP_MODULE),
+    },
+    ClassElement {
+        element_type: "audio-app",
+        suffix: "Component",
+        dedupe_suffix: true,
+        header: AUDIO_APP_COMPONENT_H_TEMPLATE,
+        source: AUDIO_APP_COMPONENT_CPP_TEMPLATE,
+        module: Some("juce::juce_audio_utils"),
+    },
+    ClassElement {
+        element_type: "look-and-feel",
+        suffix: "LookAndFeel",
+        dedupe_suffix: true,
+        header: LOOK_AND_FEEL_H_TEMPLATE,
+        source: LOOK_AND_FEEL_CPP_TEMPLATE,
+        module: Some("juce::juce_gui_basics"),
+    },
+    ClassElement {
+        element_type: "thread",
+        suffix: "Thread",
+        dedupe_suffix: true,
+        header: THREAD_H_TEMPLATE,
+        source: THREAD_CPP_TEMPLATE,
+        module: Some("juce::juce_core"),
+    },
+    ClassElement {
+        element_type: "timer-component",
+        suffix: "Component",
+        dedupe_suffix: true,
+        header: TIMER_COMPONENT_H_TEMPLATE,
+        source: TIMER_COMPONENT_CPP_TEMPLATE,
+        module: Some("juce::juce_gui_basics"),
+    },
+    ClassElement {
+        element_type: "audio-processor",
+        suffix: "Processor",
+        dedupe_suffix: true,
+        header: AUDIO_PROCESSOR_H_TEMPLATE,
+        source: AUDIO_PROCESSOR_CPP_TEMPLATE,
+        module: Some("juce::juce_audio_processors"),
+    },
+];
+
 pub fn create_cmakelists(context: &Context) -> Result<(), Box<dyn std::error::Error>> {
     let cmakelists_path = context.project_path.join("CMakeLists.txt");
     let mut cmakelists_file = File::create(cmakelists_path)?;
+    cmakelists_file.write_all(cmakelists_content(context).as_bytes())?;
+    Ok(())
+}
 
-    let cmake_content = format!(
+// The root CMakeLists.txt of a new project.
+pub fn cmakelists_content(context: &Context) -> String {
+    format!(
         indoc! {
             "cmake_minimum_required(VERSION 3.24)
-             project({} VERSION 0.0.1)
-             add_subdirectory(modules/JUCE)
+             project({} VERSION {})
+             {}
              add_subdirectory(src)"
         },
-        context.project_name
-    );
+        context.project_name,
+        context.version,
+        juce_subdirectory(context)
+    )
+}
 
-    cmakelists_file.write_all(cmake_content.as_bytes())?;
-    Ok(())
+// The add_subdirectory() call for JUCE: the bundled modules/JUCE checkout, or an existing
+// checkout outside the project, which needs an explicit binary directory.
+pub fn juce_subdirectory(context: &Context) -> String {
+    match &context.juce_path {
+        Some(juce_path) => format!(
+            "add_subdirectory(\"{}\" ${{CMAKE_BINARY_DIR}}/JUCE)",
+            juce_path.to_string_lossy().replace('\\', "/")
+        ),
+        None => String::from("add_subdirectory(modules/JUCE)"),
+    }
 }
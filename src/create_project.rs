@@ -3,17 +3,36 @@
 use crate::context::Context;
 use crate::create_files::create_cmakelists;
 use crate::create_files::create_source_files;
+use crate::create_files::{write_generated_files, GeneratedFile};
 use crate::initialize_git::create_initial_commit;
 use crate::initialize_git::initialize_git_repo;
+use crate::manifest::Manifest;
 use std::fs;
+use std::path::{Path, PathBuf};
+
+// Create the project in a hidden sibling directory and move it into place once everything
+// succeeded, so a failure (e.g. a network error while cloning JUCE) leaves nothing behind.
 pub fn create_project(context: &Context) -> Result<(), Box<dyn std::error::Error>> {
-    if context.project_path.exists() {
-        return Err(format!(
-            "Error: Project directory already exists: {}",
-            context.project_path.display()
-        )
-        .into());
-    }
+    create_project_with(context, &create_source_files)
+}
+
+// Like `create_project`, but with source files generated elsewhere (e.g. by `jumake import`).
+pub fn create_project_from_files(
+    context: &Context,
+    files: &[GeneratedFile],
+) -> Result<(), Box<dyn std::error::Error>> {
+    create_project_with(context, &|context: &Context| {
+        write_generated_files(&context.project_path, files)
+    })
+}
+
+type SourceWriter<'a> = dyn Fn(&Context) -> Result<(), Box<dyn std::error::Error>> + 'a;
+
+fn create_project_with(
+    context: &Context,
+    write_sources: &SourceWriter,
+) -> Result<(), Box<dyn std::error::Error>> {
+    validate_new_project(context)?;
 
     println!(
         "Creating project '{}' at {}...",
@@ -21,12 +40,101 @@ pub fn create_project(context: &Context) -> Result<(), Box<dyn std::error::Error
         context.project_path.display()
     );
 
-    fs::create_dir_all(&context.project_path)?;
+    let staging_path = staging_path(&context.project_path)?;
+    let staging_context = Context {
+        project_path: staging_path.clone(),
+        ..context.clone()
+    };
+    let result = populate_project(&staging_context, write_sources).and_then(|()| {
+        if context.project_path.exists() {
+            return Err(format!(
+                "Error: Project directory already exists: {}",
+                context.project_path.display()
+            )
+            .into());
+        }
+        fs::rename(&staging_path, &context.project_path)?;
+        Ok(())
+    });
+
+    if result.is_err() {
+        if context.keep_on_failure {
+            eprintln!(
+                "Kept the partially created project at {} for debugging.",
+                staging_path.display()
+            );
+        } else if staging_path.exists() {
+            // The failure that got us here matters more than a failed cleanup.
+            if let Err(e) = fs::remove_dir_all(&staging_path) {
+                eprintln!(
+                    "Warning: Could not remove the partially created project at {} ({}); please remove it by hand.",
+                    staging_path.display(),
+                    e
+                );
+            }
+        }
+    }
+    result
+}
+
+// The hidden directory next to `project_path` the project is built in, e.g. `.MyApp.jumake-1234`.
+fn staging_path(project_path: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let name = project_path
+        .file_name()
+        .ok_or(format!("Invalid project path: {}", project_path.display()))?;
+    let parent = project_path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    fs::create_dir_all(parent)?;
+    Ok(parent.join(format!(
+        ".{}.jumake-{}",
+        name.to_string_lossy(),
+        std::process::id()
+    )))
+}
+
+fn populate_project(
+    context: &Context,
+    write_sources: &SourceWriter,
+) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir(&context.project_path)?;
 
     create_cmakelists(context)?;
-    create_source_files(context)?;
+    write_sources(context)?;
+    Manifest::from_context(context).save(&context.project_path)?;
     initialize_git_repo(context)?;
     create_initial_commit(context)?;
 
     Ok(())
 }
+
+// Check everything that can be checked before a project is created.
+pub fn validate_new_project(context: &Context) -> Result<(), Box<dyn std::error::Error>> {
+    if context.project_path.exists() {
+        return Err(format!(
+            "Error: Project directory already exists: {}",
+            context.project_path.display()
+        )
+        .into());
+    }
+    validate_project_options(context)
+}
+
+// Check the JUCE checkout and plugin metadata a project is about to be set up with.
+pub fn validate_project_options(context: &Context) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(juce_path) = &context.juce_path {
+        if !juce_path.join("CMakeLists.txt").is_file() {
+            return Err(format!(
+                "Error: No JUCE checkout found at {} (missing CMakeLists.txt)",
+                juce_path.display()
+            )
+            .into());
+        }
+    }
+
+    if let Some(plugin) = &context.plugin {
+        plugin.validate()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+// src/dry_run.rs
+use crate::apvts::{plan_parameter, ParameterSpec};
+use crate::context::Context;
+use crate::create_files::{
+    cmakelists_content, generate_source_files, plan_class, ClassOptions, GeneratedFile,
+};
+use crate::create_project::validate_new_project;
+use crate::initialize_git::{gitignore_content, JUCE_SUBMODULE_PATH};
+use crate::manifest::{Manifest, MANIFEST_FILE_NAME};
+use similar::TextDiff;
+use std::error::Error;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+const DRY_RUN_NOTICE: &str = "Dry run: nothing was written.";
+
+// Describe the files and git operations of `jumake new` without touching the file system.
+pub fn dry_run_new(context: &Context) -> Result<String, Box<dyn Error>> {
+    validate_new_project(context)?;
+
+    let mut files = vec![GeneratedFile {
+        path: PathBuf::from("CMakeLists.txt"),
+        content: cmakelists_content(context).into_bytes(),
+    }];
+    files.extend(generate_source_files(context)?);
+    files.push(GeneratedFile {
+        path: PathBuf::from(MANIFEST_FILE_NAME),
+        content: Manifest::from_context(context).to_toml()?.into_bytes(),
+    });
+    files.push(GeneratedFile {
+        path: PathBuf::from(".gitignore"),
+        content: gitignore_content(context).into_bytes(),
+    });
+
+    let mut report = String::new();
+    writeln!(report, "{}", DRY_RUN_NOTICE)?;
+    writeln!(
+        report,
+        "\nFiles to create in {}:",
+        context.project_path.display()
+    )?;
+    write_file_list(&mut report, &files)?;
+
+    writeln!(report, "\nGit operations:")?;
+    writeln!(report, "  git init {}", context.project_path.display())?;
+    let mut paths: Vec<String> = files.iter().map(|file| display_path(&file.path)).collect();
+    paths.sort();
+    writeln!(report, "  git add {}", paths.join(" "))?;
+    match &context.juce_path {
+        Some(juce_path) => writeln!(
+            report,
+            "  (no JUCE submodule; CMake uses the checkout at {})",
+            juce_path.display()
+        )?,
+        None => {
+            let revision = context
+                .juce_revision
+                .as_deref()
+                .map(|revision| format!(" at {}", revision))
+                .unwrap_or_default();
+            let cache = context
+                .juce_cache_dir
+                .as_ref()
+                .map(|cache_dir| format!(" (via the JUCE cache in {})", cache_dir.display()))
+                .unwrap_or_default();
+            writeln!(
+                report,
+                "  git submodule add {} {}{}{}",
+                context.juce_source, JUCE_SUBMODULE_PATH, revision, cache
+            )?;
+        }
+    }
+    writeln!(report, "  git commit -m \"Initial commit by JuMake\"")?;
+    Ok(report)
+}
+
+// Describe the files and CMakeLists.txt edit of `jumake add` without touching the project.
+pub fn dry_run_add(
+    context: &Context,
+    element_type: &str,
+    element_name: &str,
+    options: &ClassOptions,
+) -> Result<String, Box<dyn Error>> {
+    let changes = plan_class(context, element_type, element_name, options)?;
+
+    let mut report = String::new();
+    writeln!(report, "{}", DRY_RUN_NOTICE)?;
+    writeln!(
+        report,
+        "\nFiles to create in {}:",
+        context.project_path.display()
+    )?;
+    write_file_list(&mut report, &changes.files)?;
+
+    if changes.new_cmakelists == changes.old_cmakelists {
+        writeln!(
+            report,
+            "\n{} needs no changes.",
+            display_path(&changes.cmakelists_path)
+        )?;
+    } else {
+        write_diff(
+            &mut report,
+            &changes.cmakelists_path,
+            &changes.old_cmakelists,
+            &changes.new_cmakelists,
+        )?;
+    }
+    for warning in &changes.warnings {
+        writeln!(report, "{}", warning)?;
+    }
+
+    writeln!(
+        report,
+        "\nGit operations: none (the new files are not staged)."
+    )?;
+    Ok(report)
+}
+
+// Describe the source edits of `jumake add parameter` without touching the project.
+pub fn dry_run_add_parameter(
+    context: &Context,
+    spec: &ParameterSpec,
+) -> Result<String, Box<dyn Error>> {
+    let edits = plan_parameter(context, spec)?;
+
+    let mut report = String::new();
+    writeln!(report, "{}", DRY_RUN_NOTICE)?;
+    for edit in &edits {
+        write_diff(
+            &mut report,
+            &edit.path,
+            &edit.old_content,
+            &edit.new_content,
+        )?;
+    }
+    writeln!(
+        report,
+        "\nGit operations: none (the changes are not staged)."
+    )?;
+    Ok(report)
+}
+
+fn write_diff(
+    report: &mut String,
+    path: &std::path::Path,
+    old_content: &str,
+    new_content: &str,
+) -> std::fmt::Result {
+    let path = display_path(path);
+    writeln!(report, "\nChanges to {}:", path)?;
+    let diff = TextDiff::from_lines(old_content, new_content);
+    write!(
+        report,
+        "{}",
+        diff.unified_diff()
+            .header(&format!("a/{}", path), &format!("b/{}", path))
+    )
+}
+
+fn write_file_list(report: &mut String, files: &[GeneratedFile]) -> std::fmt::Result {
+    for file in files {
+        writeln!(
+            report,
+            "  {} ({} bytes)",
+            display_path(&file.path),
+            file.content.len()
+        )?;
+    }
+    Ok(())
+}
+
+fn display_path(path: &std::path::Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
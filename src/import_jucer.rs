@@ -0,0 +1,605 @@
+// src/import_jucer.rs
+use crate::context::Context;
+use crate::create_files::{
+    find_builtin_template, render_file, GeneratedFile, SOURCES_BEGIN_MARKER, SOURCES_END_MARKER,
+};
+use crate::placeholders::project_variables;
+use crate::plugin::{validate_company_name, PluginKind, PluginOptions};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+// Attributes of <JUCERPROJECT> that `import` translates or that have no CMake equivalent.
+const HANDLED_PROJECT_ATTRIBUTES: &[&str] = &[
+    "name",
+    "projectType",
+    "version",
+    "companyName",
+    "pluginFormats",
+    "pluginCharacteristicsValue",
+    "pluginManufacturerCode",
+    "pluginCode",
+    "bundleIdentifier",
+    "defines",
+    "cppLanguageStandard",
+    "id",
+    "jucerFormatVersion",
+    "jucerVersion",
+    "displaySplashScreen",
+    "reportAppUsage",
+    "splashScreenColour",
+];
+
+// Projucer's plugin format flags and the matching FORMATS of juce_add_plugin.
+const PLUGIN_FORMAT_FLAGS: &[(&str, &str)] = &[
+    ("buildVST", "VST"),
+    ("buildVST3", "VST3"),
+    ("buildAU", "AU"),
+    ("buildAUv3", "AUv3"),
+    ("buildAAX", "AAX"),
+    ("buildStandalone", "Standalone"),
+    ("buildUnity", "Unity"),
+    ("buildLV2", "LV2"),
+];
+
+// Attributes of exporters and their configurations that are translated or only matter to the
+// Projucer's generated IDE projects.
+const HANDLED_EXPORTER_ATTRIBUTES: &[&str] = &["targetFolder", "extraDefs"];
+const HANDLED_CONFIGURATION_ATTRIBUTES: &[&str] = &["name", "isDebug", "targetName", "defines"];
+
+// A Projucer project read from a .jucer file.
+#[derive(Debug)]
+pub struct JucerProject {
+    // The directory of the .jucer file, which its file paths are relative to.
+    pub dir: PathBuf,
+    pub file_name: String,
+    pub name: String,
+    pub project_type: String,
+    attributes: BTreeMap<String, String>,
+    files: Vec<JucerFile>,
+    modules: Vec<String>,
+    juce_options: BTreeMap<String, String>,
+    exporters: Vec<JucerExporter>,
+    // Top-level elements `import` does not know.
+    unknown_elements: Vec<String>,
+}
+
+#[derive(Debug)]
+struct JucerFile {
+    path: String,
+    compile: bool,
+    resource: bool,
+}
+
+#[derive(Debug)]
+struct JucerExporter {
+    name: String,
+    attributes: BTreeMap<String, String>,
+    configurations: Vec<BTreeMap<String, String>>,
+}
+
+// The result of translating a .jucer file: the project to create and what got lost.
+pub struct JucerImport {
+    pub context: Context,
+    pub files: Vec<GeneratedFile>,
+    // Settings of the .jucer file without a counterpart in the generated project.
+    pub untranslated: Vec<String>,
+}
+
+impl JucerProject {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let document = roxmltree::Document::parse(&content)
+            .map_err(|e| format!("Invalid .jucer file {}: {}", path.display(), e))?;
+        let root = document.root_element();
+        if root.tag_name().name() != "JUCERPROJECT" {
+            return Err(format!("{} is not a Projucer project", path.display()).into());
+        }
+
+        let attributes = attribute_map(root);
+        let name = attributes
+            .get("name")
+            .cloned()
+            .ok_or(format!("{} has no project name", path.display()))?;
+        let mut project = JucerProject {
+            dir: path.parent().unwrap_or(Path::new(".")).to_path_buf(),
+            file_name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            name,
+            project_type: attributes.get("projectType").cloned().unwrap_or_default(),
+            attributes,
+            files: Vec::new(),
+            modules: Vec::new(),
+            juce_options: BTreeMap::new(),
+            exporters: Vec::new(),
+            unknown_elements: Vec::new(),
+        };
+
+        for child in root.children().filter(|node| node.is_element()) {
+            match child.tag_name().name() {
+                "MAINGROUP" => {
+                    project.files = child
+                        .descendants()
+                        .filter(|node| node.has_tag_name("FILE"))
+                        .filter_map(|node| {
+                            Some(JucerFile {
+                                path: node.attribute("file")?.replace('\\', "/"),
+                                compile: node.attribute("compile") == Some("1"),
+                                resource: node.attribute("resource") == Some("1"),
+                            })
+                        })
+                        .collect()
+                }
+                "MODULES" => {
+                    project.modules = child
+                        .children()
+                        .filter(|node| node.has_tag_name("MODULE"))
+                        .filter_map(|node| node.attribute("id").map(str::to_string))
+                        .collect()
+                }
+                "JUCEOPTIONS" => project.juce_options = attribute_map(child),
+                "EXPORTFORMATS" => {
+                    project.exporters = child
+                        .children()
+                        .filter(|node| node.is_element())
+                        .map(|exporter| JucerExporter {
+                            name: exporter.tag_name().name().to_string(),
+                            attributes: attribute_map(exporter),
+                            configurations: exporter
+                                .descendants()
+                                .filter(|node| node.has_tag_name("CONFIGURATION"))
+                                .map(attribute_map)
+                                .collect(),
+                        })
+                        .collect()
+                }
+                // Only used by the Projucer's live build engine.
+                "LIVE_SETTINGS" => {}
+                other => project.unknown_elements.push(other.to_string()),
+            }
+        }
+        Ok(project)
+    }
+
+    // The .jucer name without whitespace, so it can name the CMake project and target.
+    pub fn project_name(&self) -> String {
+        self.name.split_whitespace().collect()
+    }
+
+    // The built-in template matching the Projucer project type.
+    pub fn template_name(&self) -> Result<&'static str, String> {
+        match self.project_type.as_str() {
+            "guiapp" => Ok("GuiApplication"),
+            "audioplug" => Ok("AudioPlugin"),
+            "consoleapp" => Ok("ConsoleApp"),
+            "library" => Ok("StaticLibrary"),
+            other => Err(format!(
+                "Projucer project type '{}' cannot be imported; supported are guiapp, audioplug, \
+                 consoleapp and library",
+                other
+            )),
+        }
+    }
+
+    // Translate the project into `context` (which names the new project and its JUCE source)
+    // and the files of its src directory.
+    pub fn translate(&self, context: &Context) -> Result<JucerImport, Box<dyn Error>> {
+        let template_name = self.template_name()?;
+        let mut untranslated = Vec::new();
+        let mut context = Context {
+            template_name: Some(template_name.to_string()),
+            template_dir: None,
+            ..context.clone()
+        };
+        if let Some(version) = self.attribute("version") {
+            context.version = version.to_string();
+        }
+        if let Some(company) = self.attribute("companyName") {
+            validate_company_name(company)?;
+            context.company = Some(company.to_string());
+        }
+        if template_name == "AudioPlugin" {
+            context.plugin = Some(self.plugin_options(&context, &mut untranslated));
+        }
+
+        for (name, value) in &self.attributes {
+            if !HANDLED_PROJECT_ATTRIBUTES.contains(&name.as_str())
+                && !name.starts_with("build")
+                && !name.starts_with("pluginIs")
+                && !name.starts_with("pluginWants")
+                && !name.starts_with("pluginProduces")
+                && !value.is_empty()
+            {
+                untranslated.push(format!("project setting {}=\"{}\"", name, value));
+            }
+        }
+        for element in &self.unknown_elements {
+            untranslated.push(format!("<{}> section", element));
+        }
+
+        // Copy the project files into src/, dropping Projucer's conventional Source/ directory.
+        let mut files = Vec::new();
+        let mut sources = Vec::new();
+        let mut resources = Vec::new();
+        for file in &self.files {
+            let relative = Path::new(&file.path);
+            let inside = relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+            if !inside {
+                untranslated.push(format!(
+                    "file {} (outside the project directory, not copied)",
+                    file.path
+                ));
+                continue;
+            }
+            let content = match fs::read(self.dir.join(relative)) {
+                Ok(content) => content,
+                Err(e) => {
+                    untranslated.push(format!("file {} ({})", file.path, e));
+                    continue;
+                }
+            };
+            let target = file
+                .path
+                .strip_prefix("Source/")
+                .unwrap_or(&file.path)
+                .to_string();
+            if file.resource {
+                resources.push(target.clone());
+            } else if file.compile {
+                sources.push(target.clone());
+            }
+            files.push(GeneratedFile {
+                path: PathBuf::from("src").join(&target),
+                content,
+            });
+        }
+
+        let cmakelists = self.cmakelists(&context, &sources, &resources, &mut untranslated)?;
+        files.push(GeneratedFile {
+            path: PathBuf::from("src").join("CMakeLists.txt"),
+            content: cmakelists.into_bytes(),
+        });
+
+        Ok(JucerImport {
+            context,
+            files,
+            untranslated,
+        })
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .get(name)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+
+    // Whether a Projucer flag is set, either in a list attribute (current .jucer files) or as an
+    // attribute of its own (older ones).
+    fn flag(&self, list_attribute: &str, flag: &str) -> bool {
+        self.attribute(list_attribute)
+            .is_some_and(|list| list.split(',').any(|value| value.trim() == flag))
+            || self.attribute(flag) == Some("1")
+    }
+
+    fn plugin_options(&self, context: &Context, untranslated: &mut Vec<String>) -> PluginOptions {
+        let defaults = PluginOptions::defaults(&context.project_name, context.company.as_deref());
+        let characteristic = |flag| self.flag("pluginCharacteristicsValue", flag);
+        let formats: Vec<String> = PLUGIN_FORMAT_FLAGS
+            .iter()
+            .filter(|(flag, _)| self.flag("pluginFormats", flag))
+            .map(|(_, format)| format.to_string())
+            .collect();
+
+        let mut options = PluginOptions {
+            manufacturer_code: self
+                .attribute("pluginManufacturerCode")
+                .unwrap_or(&defaults.manufacturer_code)
+                .to_string(),
+            plugin_code: self
+                .attribute("pluginCode")
+                .unwrap_or(&defaults.plugin_code)
+                .to_string(),
+            bundle_id: self
+                .attribute("bundleIdentifier")
+                .unwrap_or(&defaults.bundle_id)
+                .to_string(),
+            kind: if characteristic("pluginIsMidiEffectPlugin") {
+                PluginKind::MidiEffect
+            } else if characteristic("pluginIsSynth") {
+                PluginKind::Synth
+            } else {
+                PluginKind::Effect
+            },
+            midi_input: characteristic("pluginWantsMidiIn"),
+            midi_output: characteristic("pluginProducesMidiOut"),
+            formats: if formats.is_empty() {
+                defaults.formats.clone()
+            } else {
+                formats
+            },
+            apvts: false,
+        };
+        if options.kind == PluginKind::MidiEffect && !(options.midi_input && options.midi_output) {
+            untranslated.push(String::from(
+                "MIDI effect without MIDI input or output; enabled both",
+            ));
+            options.midi_input = true;
+            options.midi_output = true;
+        }
+        if let Err(e) = options.validate() {
+            untranslated.push(format!(
+                "plugin metadata ({}); using jumake's defaults instead",
+                e
+            ));
+            options = PluginOptions {
+                kind: options.kind,
+                midi_input: options.midi_input,
+                midi_output: options.midi_output,
+                ..defaults
+            };
+        }
+
+        let characteristics = self
+            .attribute("pluginCharacteristicsValue")
+            .unwrap_or_default();
+        for characteristic in characteristics.split(',').map(str::trim) {
+            if !characteristic.is_empty()
+                && ![
+                    "pluginIsSynth",
+                    "pluginIsMidiEffectPlugin",
+                    "pluginWantsMidiIn",
+                    "pluginProducesMidiOut",
+                ]
+                .contains(&characteristic)
+            {
+                untranslated.push(format!("plugin characteristic {}", characteristic));
+            }
+        }
+        if let Some(formats) = self.attribute("pluginFormats") {
+            for format in formats.split(',').map(str::trim) {
+                if !format.is_empty()
+                    && !PLUGIN_FORMAT_FLAGS.iter().any(|(flag, _)| *flag == format)
+                {
+                    untranslated.push(format!("plugin format {}", format));
+                }
+            }
+        }
+        options
+    }
+
+    // The built-in template's src/CMakeLists.txt with the project's sources, resources, modules
+    // and preprocessor definitions.
+    fn cmakelists(
+        &self,
+        context: &Context,
+        sources: &[String],
+        resources: &[String],
+        untranslated: &mut Vec<String>,
+    ) -> Result<String, Box<dyn Error>> {
+        let template = find_builtin_template(context.template_name.as_deref().unwrap_or_default())
+            .ok_or("Built-in template not found")?;
+        let (file_name, template_content) = template
+            .files
+            .iter()
+            .find(|(file_name, _)| *file_name == "src/CMakeLists.txt")
+            .ok_or("The template has no src/CMakeLists.txt")?;
+        let rendered = render_file(file_name, template_content, &project_variables(context))?;
+        let mut content =
+            replace_managed_sources(&String::from_utf8_lossy(&rendered.content), sources)
+                .ok_or("The template has no JUMAKE source markers")?;
+
+        // Projucer defines (project, JUCE module options, exporters) as generator expressions.
+        let mut definitions: Vec<String> = Vec::new();
+        for (name, value) in &self.juce_options {
+            match value.as_str() {
+                "1" | "enabled" => definitions.push(format!("{}=1", name)),
+                "0" | "disabled" => definitions.push(format!("{}=0", name)),
+                "default" | "" => {}
+                other => untranslated.push(format!("JUCE option {}=\"{}\"", name, other)),
+            }
+        }
+        definitions.extend(split_definitions(self.attribute("defines")));
+        for exporter in &self.exporters {
+            let Some(platform) = exporter_platform(&exporter.name) else {
+                untranslated.push(format!(
+                    "exporter {} (no CMake equivalent; only XCODE_MAC, VS20xx and LINUX_MAKE \
+                     are translated)",
+                    exporter.name
+                ));
+                continue;
+            };
+            let platform_condition = format!("$<PLATFORM_ID:{}>", platform);
+            for definition in
+                split_definitions(exporter.attributes.get("extraDefs").map(String::as_str))
+            {
+                definitions.push(format!("$<{}:{}>", platform_condition, definition));
+            }
+            report_attributes(
+                &exporter.attributes,
+                HANDLED_EXPORTER_ATTRIBUTES,
+                &format!("exporter {}", exporter.name),
+                untranslated,
+            );
+
+            for configuration in &exporter.configurations {
+                let config_name = configuration.get("name").cloned().unwrap_or_default();
+                for definition in
+                    split_definitions(configuration.get("defines").map(String::as_str))
+                {
+                    definitions.push(format!(
+                        "$<$<AND:{},$<CONFIG:{}>>:{}>",
+                        platform_condition, config_name, definition
+                    ));
+                }
+                report_attributes(
+                    configuration,
+                    HANDLED_CONFIGURATION_ATTRIBUTES,
+                    &format!("exporter {} configuration {}", exporter.name, config_name),
+                    untranslated,
+                );
+            }
+        }
+
+        // Definitions the template already sets are updated in place instead of repeated.
+        let mut extra_definitions = Vec::new();
+        for definition in definitions {
+            let name = definition.split('=').next().unwrap_or_default();
+            let existing = Regex::new(&format!(r"(?m)^(\s*){}=[^\s)]*", regex::escape(name)))?;
+            if !definition.starts_with('$') && existing.is_match(&content) {
+                content = existing
+                    .replace(&content, |captures: &regex::Captures| {
+                        format!("{}{}", &captures[1], definition)
+                    })
+                    .into_owned();
+            } else {
+                extra_definitions.push(definition);
+            }
+        }
+
+        let mut libraries = Vec::new();
+        if !resources.is_empty() {
+            libraries.push(String::from("${PROJECT_NAME}_BinaryData"));
+        }
+        for module in &self.modules {
+            if !module.starts_with("juce_") {
+                untranslated.push(format!(
+                    "module {} (not a JUCE module; add it with juce_add_module)",
+                    module
+                ));
+            } else if !content.contains(&format!("juce::{}", module)) {
+                libraries.push(format!("juce::{}", module));
+            }
+        }
+
+        content.push_str(&format!("\n\n# Imported from {}\n", self.file_name));
+        // JUCE only generates JuceHeader.h for targets created by its juce_add_* functions.
+        let juce_target = Regex::new(r"(?m)^\s*juce_add_(gui_app|plugin|console_app)\s*\(")?;
+        if juce_target.is_match(&content) {
+            content.push_str("juce_generate_juce_header(${PROJECT_NAME})\n");
+        } else {
+            untranslated.push(String::from(
+                "JuceHeader.h (juce_generate_juce_header needs a juce_add_* target; include the \
+                 JUCE module headers directly)",
+            ));
+        }
+        if !resources.is_empty() {
+            content.push_str(&cmake_call(
+                "juce_add_binary_data(${PROJECT_NAME}_BinaryData",
+                "SOURCES",
+                resources,
+            ));
+        }
+        if !extra_definitions.is_empty() {
+            content.push_str(&cmake_call(
+                "target_compile_definitions(${PROJECT_NAME}",
+                "PRIVATE",
+                &extra_definitions,
+            ));
+        }
+        if !libraries.is_empty() {
+            content.push_str(&cmake_call(
+                "target_link_libraries(${PROJECT_NAME}",
+                "PRIVATE",
+                &libraries,
+            ));
+        }
+        match self.attribute("cppLanguageStandard") {
+            Some("latest") => untranslated.push(String::from("C++ language standard \"latest\"")),
+            Some(standard) => content.push_str(&format!(
+                "\ntarget_compile_features(${{PROJECT_NAME}} PRIVATE cxx_std_{})\n",
+                standard
+            )),
+            None => {}
+        }
+        Ok(content)
+    }
+}
+
+fn attribute_map(node: roxmltree::Node) -> BTreeMap<String, String> {
+    node.attributes()
+        .map(|attribute| (attribute.name().to_string(), attribute.value().to_string()))
+        .collect()
+}
+
+// Report the non-empty attributes that are not in `handled`.
+fn report_attributes(
+    attributes: &BTreeMap<String, String>,
+    handled: &[&str],
+    owner: &str,
+    untranslated: &mut Vec<String>,
+) {
+    let names: Vec<&str> = attributes
+        .iter()
+        .filter(|(name, value)| !handled.contains(&name.as_str()) && !value.is_empty())
+        .map(|(name, _)| name.as_str())
+        .collect();
+    if !names.is_empty() {
+        untranslated.push(format!("{} settings: {}", owner, names.join(", ")));
+    }
+}
+
+// The CMAKE_SYSTEM_NAME an exporter builds for, if it has a CMake counterpart.
+fn exporter_platform(exporter: &str) -> Option<&'static str> {
+    match exporter {
+        "XCODE_MAC" => Some("Darwin"),
+        "LINUX_MAKE" => Some("Linux"),
+        name if name.starts_with("VS20") => Some("Windows"),
+        _ => None,
+    }
+}
+
+// Projucer separates preprocessor definitions by whitespace or new lines.
+fn split_definitions(definitions: Option<&str>) -> Vec<String> {
+    definitions
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+// Replace the lines between the JUMAKE source markers with `sources`.
+fn replace_managed_sources(content: &str, sources: &[String]) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let begin_idx = lines
+        .iter()
+        .position(|line| line.contains(SOURCES_BEGIN_MARKER))?;
+    let end_idx = lines
+        .iter()
+        .position(|line| line.contains(SOURCES_END_MARKER))?;
+    if end_idx <= begin_idx {
+        return None;
+    }
+    let indent: String = lines[end_idx]
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect();
+
+    let mut updated: Vec<String> = lines[..=begin_idx]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+    updated.extend(sources.iter().map(|source| format!("{}{}", indent, source)));
+    updated.extend(lines[end_idx..].iter().map(|line| line.to_string()));
+    Some(updated.join("\n"))
+}
+
+// A CMake call in the layout of the built-in templates, e.g.
+//
+//     target_link_libraries(${PROJECT_NAME}
+//         PRIVATE
+//             juce::juce_dsp)
+fn cmake_call(head: &str, keyword: &str, items: &[String]) -> String {
+    let items: Vec<String> = items
+        .iter()
+        .map(|item| format!("        {}", item))
+        .collect();
+    format!("\n{}\n    {}\n{})\n", head, keyword, items.join("\n"))
+}
@@ -0,0 +1,324 @@
+// src/init_project.rs
+use crate::context::Context;
+use crate::create_files::{
+    add_source_markers, cmakelists_content, generate_source_files, juce_subdirectory,
+    write_generated_files, GeneratedFile,
+};
+use crate::create_project::validate_project_options;
+use crate::initialize_git::{
+    add_all_files_to_repo, add_juce_submodule, create_initial_commit, gitignore_content,
+};
+use crate::manifest::{extract_project_name_and_version, Manifest, MANIFEST_FILE_NAME};
+use git2::Repository;
+use regex::Regex;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The template name recorded for CMake projects without a juce_add_* call.
+pub const CUSTOM_TEMPLATE_NAME: &str = "Custom";
+
+// What `jumake init` found in a directory that already has a CMakeLists.txt.
+#[derive(Debug)]
+pub struct ExistingProject {
+    // From the project() call of the root CMakeLists.txt.
+    pub name: Option<String>,
+    pub version: Option<String>,
+    // The JUMAKE_TEMPLATE marker, or the template matching the juce_add_* call.
+    pub template_name: Option<String>,
+    // The CMakeLists.txt with the juce_add_* call, relative to the project directory.
+    pub target_cmakelists: Option<PathBuf>,
+    // Whether the CMake code already brings in JUCE (add_subdirectory, find_package, ...).
+    pub provides_juce: bool,
+}
+
+// Look at the CMake project in `project_path`; None if there is no root CMakeLists.txt.
+pub fn detect_project(project_path: &Path) -> Result<Option<ExistingProject>, Box<dyn Error>> {
+    let root_path = project_path.join("CMakeLists.txt");
+    if !root_path.is_file() {
+        return Ok(None);
+    }
+    let root = fs::read_to_string(&root_path)?;
+    let (name, version) = match extract_project_name_and_version(&root) {
+        Some((name, version)) => (Some(name), version),
+        None => (None, None),
+    };
+
+    let juce_add = juce_add_regex();
+    let marker = Regex::new(r#"set\(JUMAKE_TEMPLATE\s+"([^"]+)"\)"#).unwrap();
+    let juce_source = Regex::new(
+        r"(?i)(add_subdirectory|find_package|FetchContent_Declare|CPMAddPackage)\s*\([^)]*juce",
+    )
+    .unwrap();
+
+    let mut provides_juce = juce_source.is_match(&root);
+    let mut template_name = None;
+    let mut target_cmakelists = None;
+    for candidate in [Path::new("src/CMakeLists.txt"), Path::new("CMakeLists.txt")] {
+        let Ok(content) = fs::read_to_string(project_path.join(candidate)) else {
+            continue;
+        };
+        provides_juce |= juce_source.is_match(&content);
+        let Some(captures) = juce_add.captures(&content) else {
+            continue;
+        };
+        template_name = Some(match marker.captures(&content) {
+            Some(captures) => captures[1].to_string(),
+            None => match &captures[1] {
+                "gui_app" => String::from("GuiApplication"),
+                "plugin" => String::from("AudioPlugin"),
+                _ => String::from("ConsoleApp"),
+            },
+        });
+        target_cmakelists = Some(candidate.to_path_buf());
+        break;
+    }
+
+    Ok(Some(ExistingProject {
+        name,
+        version,
+        template_name,
+        target_cmakelists,
+        provides_juce,
+    }))
+}
+
+// Turn `context.project_path` into a jumake project. An empty directory gets the files of the
+// template; an existing CMake project gets the JUMAKE markers where they can be inserted. JUCE
+// and git are only added if the project does not have them yet.
+pub fn init_project(context: &Context) -> Result<(), Box<dyn Error>> {
+    let project_path = &context.project_path;
+    if project_path.join(MANIFEST_FILE_NAME).exists() {
+        return Err(format!(
+            "{} already has a {}",
+            project_path.display(),
+            MANIFEST_FILE_NAME
+        )
+        .into());
+    }
+    validate_project_options(context)?;
+
+    println!(
+        "Initializing project '{}' in {}...",
+        context.project_name,
+        project_path.display()
+    );
+    let (mut written, provides_juce) = match detect_project(project_path)? {
+        Some(existing) => (
+            adopt_cmake_project(context, &existing)?,
+            existing.provides_juce,
+        ),
+        None => (create_template_files(context)?, false),
+    };
+
+    Manifest::from_context(context).save(project_path)?;
+    println!("Created file: {}", MANIFEST_FILE_NAME);
+    written.push(PathBuf::from(MANIFEST_FILE_NAME));
+    if update_gitignore(context)? {
+        written.push(PathBuf::from(".gitignore"));
+    }
+
+    let (mut repo, created) = match Repository::open(project_path) {
+        Ok(repo) => (repo, false),
+        Err(_) => {
+            println!("Initializing Git repository...");
+            (Repository::init(project_path)?, true)
+        }
+    };
+    if created {
+        add_all_files_to_repo(&repo)?;
+    } else {
+        let mut index = repo.index()?;
+        for path in &written {
+            index.add_path(path)?;
+        }
+        index.write()?;
+    }
+
+    match &context.juce_path {
+        Some(juce_path) => println!("Using existing JUCE checkout at {}", juce_path.display()),
+        None if provides_juce => println!("The project already adds JUCE, skipping JUCE setup."),
+        None => add_juce_submodule(&mut repo, context)?,
+    }
+
+    if created {
+        create_initial_commit(context)?;
+    } else {
+        println!("The changes are staged; commit them with `git commit`.");
+    }
+    println!("Project '{}' initialized.", context.project_name);
+    Ok(())
+}
+
+// Write the root CMakeLists.txt and the template files into a directory without CMake project.
+fn create_template_files(context: &Context) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files = vec![GeneratedFile {
+        path: PathBuf::from("CMakeLists.txt"),
+        content: cmakelists_content(context).into_bytes(),
+    }];
+    files.extend(generate_source_files(context)?);
+
+    let existing: Vec<String> = files
+        .iter()
+        .filter(|file| context.project_path.join(&file.path).exists())
+        .map(|file| file.path.display().to_string())
+        .collect();
+    if !existing.is_empty() {
+        return Err(format!(
+            "The template would overwrite existing files: {}",
+            existing.join(", ")
+        )
+        .into());
+    }
+
+    write_generated_files(&context.project_path, &files)?;
+    Ok(files.into_iter().map(|file| file.path).collect())
+}
+
+// Insert the JUMAKE markers into an existing CMake project and make it add JUCE if it does not.
+fn adopt_cmake_project(
+    context: &Context,
+    existing: &ExistingProject,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut written = Vec::new();
+    let root_path = PathBuf::from("CMakeLists.txt");
+
+    match &existing.target_cmakelists {
+        Some(target_path) => {
+            let content = fs::read_to_string(context.project_path.join(target_path))?;
+            let template_name = context
+                .template_name
+                .as_deref()
+                .unwrap_or(CUSTOM_TEMPLATE_NAME);
+            let mut updated = insert_template_marker(&content, template_name);
+            match add_source_markers(&updated) {
+                Some(marked) => updated = marked,
+                None if content.contains("JUMAKE_SOURCES_BEGIN") => {}
+                None => println!(
+                    "Could not find a multi-line target_sources(... PRIVATE ...) block in {}; \
+                     `jumake add` will append a managed block instead.",
+                    target_path.display()
+                ),
+            }
+            if updated != content {
+                fs::write(context.project_path.join(target_path), &updated)?;
+                println!("Added JUMAKE markers to {}", target_path.display());
+                written.push(target_path.clone());
+            }
+            if target_path.as_path() != Path::new("src/CMakeLists.txt") {
+                println!(
+                    "Note: `jumake add` puts new classes into src/ and src/CMakeLists.txt, \
+                     but this project's target is defined in {}.",
+                    target_path.display()
+                );
+            }
+        }
+        None => println!(
+            "No juce_add_gui_app/juce_add_plugin/juce_add_console_app call found; \
+             no JUMAKE markers were inserted."
+        ),
+    }
+
+    if !existing.provides_juce {
+        let path = context.project_path.join(&root_path);
+        let content = fs::read_to_string(&path)?;
+        fs::write(
+            &path,
+            insert_after_project_call(&content, &juce_subdirectory(context)),
+        )?;
+        println!("Added JUCE to {}", root_path.display());
+        if !written.contains(&root_path) {
+            written.push(root_path);
+        }
+    }
+    Ok(written)
+}
+
+// The juce_add_* call of the project's target, which decides its template.
+fn juce_add_regex() -> Regex {
+    Regex::new(r"juce_add_(gui_app|plugin|console_app)\s*\(").unwrap()
+}
+
+// Put `set(JUMAKE_TEMPLATE "...")` in front of the target's juce_add_* call, unless it is already
+// there. Other calls such as juce_add_binary_data() may come first.
+fn insert_template_marker(content: &str, template_name: &str) -> String {
+    if content.contains("JUMAKE_TEMPLATE") {
+        return content.to_string();
+    }
+    let Some(call) = juce_add_regex().find(content) else {
+        return content.to_string();
+    };
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let idx = content[..call.start()].matches('\n').count();
+    let indent: String = lines[idx]
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect();
+    lines.insert(idx, String::new());
+    lines.insert(
+        idx,
+        format!("{}set(JUMAKE_TEMPLATE \"{}\")", indent, template_name),
+    );
+    join_lines(content, lines)
+}
+
+// Insert `line` after the (possibly multi-line) project() call, or at the top if there is none.
+fn insert_after_project_call(content: &str, line: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let start = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with("project("))
+        .or_else(|| {
+            lines
+                .iter()
+                .position(|line| line.trim_start().starts_with("cmake_minimum_required("))
+        });
+    let insert_idx = match start {
+        Some(start) => {
+            let mut depth = 0;
+            let mut end = start;
+            for (idx, line) in lines.iter().enumerate().skip(start) {
+                depth += line.matches('(').count() as i32 - line.matches(')').count() as i32;
+                end = idx;
+                if depth <= 0 {
+                    break;
+                }
+            }
+            end + 1
+        }
+        None => 0,
+    };
+    lines.insert(insert_idx, line.to_string());
+    join_lines(content, lines)
+}
+
+fn join_lines(content: &str, lines: Vec<String>) -> String {
+    let mut joined = lines.join("\n");
+    if content.ends_with('\n') || content.is_empty() {
+        joined.push('\n');
+    }
+    joined
+}
+
+// Append the jumake entries missing from .gitignore; returns whether the file changed.
+fn update_gitignore(context: &Context) -> Result<bool, Box<dyn Error>> {
+    let path = context.project_path.join(".gitignore");
+    let mut content = fs::read_to_string(&path).unwrap_or_default();
+    let missing: Vec<String> = gitignore_content(context)
+        .lines()
+        .filter(|entry| !content.lines().any(|line| line.trim() == *entry))
+        .map(str::to_string)
+        .collect();
+    if missing.is_empty() {
+        return Ok(false);
+    }
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    for entry in missing {
+        content.push_str(&entry);
+        content.push('\n');
+    }
+    fs::write(path, content)?;
+    Ok(true)
+}
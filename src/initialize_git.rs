@@ -1,17 +1,20 @@
 // scr/initialize_git.rs
+use crate::cache::{copy_cached_objects, ensure_cached};
 use crate::context::Context;
 use git2::{
-    build::CheckoutBuilder, Error, FetchOptions, IndexAddOption, RemoteCallbacks, Repository,
-    Signature,
+    build::CheckoutBuilder, Direction, Error, FetchOptions, IndexAddOption, Oid, Remote,
+    RemoteCallbacks, Repository, Signature,
 };
-use std::fs;
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::Path;
+
+pub const JUCE_SUBMODULE_PATH: &str = "modules/JUCE";
+
 // Initialize the Git repository
 pub fn initialize_git_repo(context: &Context) -> Result<(), Box<dyn std::error::Error>> {
     println!("Initializing Git repository...");
-    let repo = Repository::init(&context.project_path)?;
+    let mut repo = Repository::init(&context.project_path)?;
     println!("Git repository initialized successfully.");
 
     let mut gitignore_file = OpenOptions::new()
@@ -19,133 +22,325 @@ pub fn initialize_git_repo(context: &Context) -> Result<(), Box<dyn std::error::
         .create(true)
         .open(context.project_path.join(".gitignore"))?;
 
-    writeln!(gitignore_file, "modules/")?;
-    writeln!(gitignore_file, "jumake_build/")?;
-    writeln!(gitignore_file, "build/")?;
-    writeln!(gitignore_file, "compile_commands.json")?;
-    writeln!(gitignore_file, ".jumake")?;
+    gitignore_file.write_all(gitignore_content(context).as_bytes())?;
 
     // Add all files to the repository (excluding the submodule)
     add_all_files_to_repo(&repo)?;
 
-    // Add JUCE as a submodule
-    add_juce_submodule(context)?;
+    // Add JUCE as a submodule, unless the project uses an existing checkout elsewhere
+    match &context.juce_path {
+        Some(juce_path) => println!("Using existing JUCE checkout at {}", juce_path.display()),
+        None => add_juce_submodule(&mut repo, context)?,
+    }
 
-    // Stage the .gitmodules file if present
-    let gitmodules_path = context.project_path.join(".gitmodules");
-    if gitmodules_path.exists() {
-        let mut index = repo.index()?;
-        index.add_path(Path::new(".gitmodules"))?;
-        index.write()?;
+    Ok(())
+}
+// The entries jumake adds to the project's .gitignore.
+pub fn gitignore_content(context: &Context) -> String {
+    format!("{}/\nbuild/\ncompile_commands.json\n", context.build_dir)
+}
+
+// Register JUCE as a git submodule: its git directory lives in .git/modules, and the
+// checked-out commit is staged as a gitlink so the initial commit pins it.
+pub fn add_juce_submodule(
+    repo: &mut Repository,
+    context: &Context,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let juce_path = context.project_path.join(JUCE_SUBMODULE_PATH);
+    let submodule_url = context.juce_source.as_str();
+
+    if juce_path.exists() {
+        println!("JUCE already exists, skipping clone.");
+        return Ok(());
     }
 
+    if context.juce_cache_dir.is_none() {
+        println!(
+            "Cloning JUCE from {}... this may take some minutes. Please be patient!",
+            submodule_url
+        );
+    }
+
+    // Fetch JUCE into the submodule's repository directly, so that any tag, branch or commit
+    // can be checked out. This bypasses git's URL rewriting
+    let mut submodule = repo.submodule(submodule_url, Path::new(JUCE_SUBMODULE_PATH), true)?;
+    let submodule_repo = submodule.open()?;
+    let fetched = match &context.juce_cache_dir {
+        Some(cache_dir) => {
+            // Seed the submodule from the shared cache instead of the network
+            let cached = ensure_cached(cache_dir, submodule_url, context.juce_revision.as_deref())?;
+            copy_cached_objects(&cached, submodule_repo.path())?;
+            // Reopen the repository so it sees the objects copied from the cache
+            let submodule_repo = Repository::open(&juce_path)?;
+            checkout_revision(&submodule_repo, &cached.fetched)?;
+            cached.fetched
+        }
+        None => fetch_revision(
+            &submodule_repo,
+            submodule_url,
+            context.juce_revision.as_deref(),
+        )?,
+    };
+    println!(
+        "Checked out JUCE {} ({})",
+        fetched.revision,
+        short_id(fetched.commit)
+    );
+
+    // Stage the gitlink and .gitmodules
+    submodule.add_finalize()?;
+    drop(submodule);
+
+    // Record a branch in .gitmodules so `git submodule update --remote` follows it
+    if let Some(branch) = &fetched.branch {
+        repo.submodule_set_branch(JUCE_SUBMODULE_PATH, branch)?;
+    }
+    let mut index = repo.index()?;
+    index.add_path(Path::new(".gitmodules"))?;
+    index.write()?;
+
+    println!("JUCE cloned successfully");
     Ok(())
 }
-fn add_juce_submodule(context: &Context) -> Result<(), Box<dyn std::error::Error>> {
-    let modules_path = context.project_path.join("modules");
-    fs::create_dir_all(&modules_path)?;
-
-    let juce_path = modules_path.join("JUCE");
-    let submodule_url = "https://github.com/juce-framework/JUCE.git";
-
-    if !juce_path.exists() {
-        println!("Cloning JUCE from GitHub... this may take some minutes. Please be patient!");
-
-        // Clone JUCE directly using git2::build::RepoBuilder
-        // This bypasses the submodule mechanism and git's URL rewriting
-        let mut remote_callbacks = RemoteCallbacks::new();
-        let mut last_progress = 0;
-
-        remote_callbacks.transfer_progress(move |stats| {
-            if stats.received_objects() != last_progress {
-                last_progress = stats.received_objects();
-                print!(
-                    "\rReceived {}/{} objects ({} bytes)",
-                    stats.received_objects(),
-                    stats.total_objects(),
-                    stats.received_bytes()
-                );
-                let _ = std::io::stdout().flush();
-            }
-            true
-        });
 
-        remote_callbacks.credentials(|_url, username_from_url, allowed_types| {
-            let username = username_from_url.unwrap_or("git");
+// The result of fetching a revision into a repository.
+pub struct FetchedRevision {
+    // The revision as it was requested, or the remote's default branch.
+    pub revision: String,
+    // The branch name if the revision is a branch, so it can be followed later.
+    pub branch: Option<String>,
+    pub commit: Oid,
+}
 
-            // For SSH (if git config rewrites HTTPS to SSH)
-            if allowed_types.contains(git2::CredentialType::SSH_KEY) {
-                // Try SSH agent first
-                if let Ok(cred) = git2::Cred::ssh_key_from_agent(username) {
-                    return Ok(cred);
-                }
+// Fetch `revision` (a tag, branch or commit id; the remote's default branch if None) from `url`
+// into `repo` and check it out (bare repositories only get their HEAD updated). Tags and branches are fetched shallow (depth 1) from network remotes.
+pub fn fetch_revision(
+    repo: &Repository,
+    url: &str,
+    revision: Option<&str>,
+) -> Result<FetchedRevision, Box<dyn std::error::Error>> {
+    let mut remote = match repo.find_remote("origin") {
+        Ok(remote) => remote,
+        Err(_) => repo.remote("origin", url)?,
+    };
 
-                // Try default SSH key locations
-                if let Some(home) = std::env::var_os("HOME") {
-                    let home_path = std::path::Path::new(&home);
-                    let key_paths = [
-                        home_path.join(".ssh").join("id_ed25519"),
-                        home_path.join(".ssh").join("id_rsa"),
-                        home_path.join(".ssh").join("id_ecdsa"),
-                    ];
-
-                    for key_path in &key_paths {
-                        if key_path.exists() {
-                            if let Ok(cred) = git2::Cred::ssh_key(username, None, key_path, None) {
-                                return Ok(cred);
-                            }
-                        }
-                    }
+    // Ask the remote which refs it has, so we know whether the revision is a tag, branch or commit.
+    let (heads, default_branch) = {
+        let connection = remote.connect_auth(Direction::Fetch, Some(remote_callbacks()), None)?;
+        let heads = connection
+            .list()?
+            .iter()
+            .map(|head| (head.name().to_string(), head.oid()))
+            .collect::<Vec<_>>();
+        let default_branch = connection
+            .default_branch()
+            .ok()
+            .and_then(|buf| buf.as_str().map(str::to_string));
+        (heads, default_branch)
+    };
+
+    let target = match revision {
+        Some(revision) => resolve_remote_revision(&heads, revision)
+            .ok_or_else(|| format!("Revision '{}' not found in repository {}", revision, url))?,
+        None => {
+            let Some(reference_name) = default_branch else {
+                return Err("Failed to resolve default remote HEAD reference".into());
+            };
+            let Some(branch) = reference_name.strip_prefix("refs/heads/") else {
+                return Err("Failed to read default branch name".into());
+            };
+            RemoteTarget::Branch(branch.to_string())
+        }
+    };
+
+    let commit = match &target {
+        RemoteTarget::Tag(tag) => {
+            let refspec = format!("+refs/tags/{tag}:refs/tags/{tag}");
+            fetch_refspecs(&mut remote, &[refspec], true)?;
+            repo.revparse_single(&format!("refs/tags/{tag}^{{commit}}"))?
+                .id()
+        }
+        RemoteTarget::Branch(branch) => {
+            let refspec = format!("+refs/heads/{branch}:refs/remotes/origin/{branch}");
+            fetch_refspecs(&mut remote, &[refspec], true)?;
+            repo.refname_to_id(&format!("refs/remotes/origin/{branch}"))?
+        }
+        RemoteTarget::Commit(id) => {
+            // Commits can only be fetched shallow if the server allows it; otherwise fall
+            // back to fetching the full history of all branches and tags.
+            let shallow = fetch_refspecs(&mut remote, std::slice::from_ref(id), true)
+                .ok()
+                .and_then(|_| repo.revparse_single(id).ok());
+            match shallow {
+                Some(object) => object.peel_to_commit()?.id(),
+                None => {
+                    fetch_refspecs(
+                        &mut remote,
+                        &[
+                            String::from("+refs/heads/*:refs/remotes/origin/*"),
+                            String::from("+refs/tags/*:refs/tags/*"),
+                        ],
+                        false,
+                    )?;
+                    repo.revparse_single(id)
+                        .map_err(|_| format!("Revision '{}' not found in repository {}", id, url))?
+                        .peel_to_commit()?
+                        .id()
                 }
             }
+        }
+    };
 
-            // For HTTPS
-            if allowed_types.contains(git2::CredentialType::DEFAULT) {
-                return git2::Cred::default();
-            }
+    let branch = match target {
+        RemoteTarget::Branch(branch) => Some(branch),
+        _ => None,
+    };
+    let fetched = FetchedRevision {
+        revision: revision
+            .map(str::to_string)
+            .or_else(|| branch.clone())
+            .unwrap_or_default(),
+        branch,
+        commit,
+    };
+    checkout_revision(repo, &fetched)?;
+    Ok(fetched)
+}
 
-            git2::Cred::default()
-        });
+// Point HEAD at a fetched revision: branches get a local branch, everything else a detached HEAD.
+// Non-bare repositories also get their working tree checked out.
+pub fn checkout_revision(repo: &Repository, fetched: &FetchedRevision) -> Result<(), Error> {
+    match &fetched.branch {
+        Some(branch) => {
+            let reference_name = format!("refs/heads/{}", branch);
+            repo.reference(&reference_name, fetched.commit, true, "jumake: checkout")?;
+            repo.set_head(&reference_name)?;
+        }
+        None => repo.set_head_detached(fetched.commit)?,
+    }
+    if !repo.is_bare() {
+        repo.checkout_head(Some(CheckoutBuilder::default().force()))?;
+    }
+    Ok(())
+}
 
-        let mut fetch_options = FetchOptions::new();
-        fetch_options.remote_callbacks(remote_callbacks);
-        fetch_options.depth(1);
+enum RemoteTarget {
+    Tag(String),
+    Branch(String),
+    Commit(String),
+}
 
-        let mut builder = git2::build::RepoBuilder::new();
-        builder.fetch_options(fetch_options);
-
-        let submodule_repo = builder.clone(submodule_url, &juce_path)?;
-        println!("\nFetched JUCE using shallow clone (depth 1)");
-
-        // Checkout the remote default HEAD branch.
-        let (object, reference) = submodule_repo.revparse_ext("refs/remotes/origin/HEAD")?;
-        submodule_repo.checkout_tree(&object, Some(CheckoutBuilder::default().force()))?;
-        let Some(reference) = reference else {
-            return Err("Failed to resolve default remote HEAD reference".into());
-        };
-        let Some(reference_name) = reference.name() else {
-            return Err("Failed to read default branch name".into());
-        };
-        submodule_repo.set_head(reference_name)?;
-
-        // Now add it to parent repo as a submodule by creating .gitmodules
-        let gitmodules_path = context.project_path.join(".gitmodules");
-        let gitmodules_content = format!(
-            "[submodule \"modules/JUCE\"]\n\tpath = modules/JUCE\n\turl = {}\n",
-            submodule_url
-        );
-        fs::write(gitmodules_path, gitmodules_content)?;
+fn resolve_remote_revision(heads: &[(String, Oid)], revision: &str) -> Option<RemoteTarget> {
+    let has_ref = |name: String| heads.iter().any(|(head, _)| *head == name);
 
-        println!("JUCE cloned successfully");
-    } else {
-        println!("JUCE already exists, skipping clone.");
+    if has_ref(format!("refs/tags/{}", revision)) {
+        return Some(RemoteTarget::Tag(revision.to_string()));
+    }
+    if has_ref(format!("refs/heads/{}", revision)) {
+        return Some(RemoteTarget::Branch(revision.to_string()));
     }
+    if let Some(tag) = revision.strip_prefix("refs/tags/") {
+        return has_ref(revision.to_string()).then(|| RemoteTarget::Tag(tag.to_string()));
+    }
+    if let Some(branch) = revision.strip_prefix("refs/heads/") {
+        return has_ref(revision.to_string()).then(|| RemoteTarget::Branch(branch.to_string()));
+    }
+
+    let is_commit_id =
+        (4..=40).contains(&revision.len()) && revision.chars().all(|c| c.is_ascii_hexdigit());
+    if !is_commit_id {
+        return None;
+    }
+    // Prefer the full id of an advertised ref tip, since abbreviated ids cannot be fetched.
+    let revision = revision.to_ascii_lowercase();
+    let full_id = heads
+        .iter()
+        .map(|(_, oid)| oid.to_string())
+        .find(|oid| oid.starts_with(&revision))
+        .unwrap_or(revision);
+    Some(RemoteTarget::Commit(full_id))
+}
 
+fn fetch_refspecs(remote: &mut Remote, refspecs: &[String], shallow: bool) -> Result<(), Error> {
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(remote_callbacks());
+    // libgit2's local transport cannot fetch shallow, local repositories are fetched in full.
+    if shallow && !is_local_url(remote.url().unwrap_or_default()) {
+        fetch_options.depth(1);
+    }
+    remote.fetch(refspecs, Some(&mut fetch_options), None)?;
+    println!();
     Ok(())
 }
 
+fn is_local_url(url: &str) -> bool {
+    url.starts_with("file://") || Path::new(url).exists()
+}
+
+fn remote_callbacks<'a>() -> RemoteCallbacks<'a> {
+    let mut remote_callbacks = RemoteCallbacks::new();
+    let mut last_progress = 0;
+
+    remote_callbacks.transfer_progress(move |stats| {
+        if stats.received_objects() != last_progress {
+            last_progress = stats.received_objects();
+            print!(
+                "\rReceived {}/{} objects ({} bytes)",
+                stats.received_objects(),
+                stats.total_objects(),
+                stats.received_bytes()
+            );
+            let _ = std::io::stdout().flush();
+        }
+        true
+    });
+
+    remote_callbacks.credentials(|_url, username_from_url, allowed_types| {
+        let username = username_from_url.unwrap_or("git");
+
+        // For SSH (if git config rewrites HTTPS to SSH)
+        if allowed_types.contains(git2::CredentialType::SSH_KEY) {
+            // Try SSH agent first
+            if let Ok(cred) = git2::Cred::ssh_key_from_agent(username) {
+                return Ok(cred);
+            }
+
+            // Try default SSH key locations
+            if let Some(home) = std::env::var_os("HOME") {
+                let home_path = std::path::Path::new(&home);
+                let key_paths = [
+                    home_path.join(".ssh").join("id_ed25519"),
+                    home_path.join(".ssh").join("id_rsa"),
+                    home_path.join(".ssh").join("id_ecdsa"),
+                ];
+
+                for key_path in &key_paths {
+                    if key_path.exists() {
+                        if let Ok(cred) = git2::Cred::ssh_key(username, None, key_path, None) {
+                            return Ok(cred);
+                        }
+                    }
+                }
+            }
+        }
+
+        // For HTTPS
+        if allowed_types.contains(git2::CredentialType::DEFAULT) {
+            return git2::Cred::default();
+        }
+
+        git2::Cred::default()
+    });
+
+    remote_callbacks
+}
+
+fn short_id(oid: Oid) -> String {
+    oid.to_string()[..10].to_string()
+}
+
 // Add all files to the Git repository index
-fn add_all_files_to_repo(repo: &Repository) -> Result<(), Error> {
+pub fn add_all_files_to_repo(repo: &Repository) -> Result<(), Error> {
     let mut index = repo.index()?;
     index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None)?;
     index.write()?;
@@ -162,14 +357,15 @@ pub fn create_initial_commit(context: &Context) -> Result<(), Error> {
         index.write_tree()?
     };
     let tree = repo.find_tree(tree_id)?;
-    let commit_id = repo.commit(
-        Some("HEAD"),
-        &signature,
-        &signature,
-        "Initial commit by JuMake",
-        &tree,
-        &[],
-    )?;
+    let mut message = String::from("Initial commit by JuMake");
+    if let Some(revision) = &context.juce_revision {
+        let juce_head = Repository::open(context.project_path.join(JUCE_SUBMODULE_PATH))
+            .and_then(|juce_repo| juce_repo.head()?.peel_to_commit().map(|c| c.id()));
+        if let Ok(juce_head) = juce_head {
+            message.push_str(&format!("\n\nJUCE pinned to {} ({})", revision, juce_head));
+        }
+    }
+    let commit_id = repo.commit(Some("HEAD"), &signature, &signature, &message, &tree, &[])?;
     println!("Initial commit created with id: {}", commit_id);
     Ok(())
 }
@@ -1,5 +1,16 @@
 // scr/lib.rs
 
+pub mod apvts;
+pub mod cache;
 pub mod context;
 pub mod create_files;
+pub mod create_project;
+pub mod dry_run;
+pub mod import_jucer;
+pub mod init_project;
 pub mod initialize_git;
+pub mod manifest;
+pub mod placeholders;
+pub mod plugin;
+pub mod templates;
+pub mod update_juce;
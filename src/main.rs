@@ -1,45 +1,68 @@
 // scr/main.rs
 
-use clap::{Parser, Subcommand, ValueEnum};
-use dialoguer::{theme::ColorfulTheme, Select};
-use regex::Regex;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Select};
+use std::collections::BTreeSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+mod apvts;
 mod build;
+mod cache;
 mod context;
+use apvts::{add_parameter, ParameterControl, ParameterSpec, ParameterType};
 use build::{build_project, run_project};
-use context::Context;
+use cache::{
+    default_cache_dir, directory_size, list_cached_revisions, prune_cache, update_cache,
+    CACHE_DIR_ENV_VAR,
+};
+use context::{resolve_juce_url, Context};
 mod create_project;
-use create_project::create_project;
+use create_project::{create_project, create_project_from_files};
+use dry_run::{dry_run_add, dry_run_add_parameter, dry_run_new};
 mod create_files;
+mod dry_run;
+mod import_jucer;
+mod init_project;
 mod initialize_git;
-use create_files::add_class;
+mod manifest;
+mod placeholders;
+mod plugin;
+mod templates;
+mod update_juce;
+use create_files::{
+    add_class, find_builtin_template, BuiltinTemplate, ClassOptions, BUILTIN_TEMPLATES,
+};
+use import_jucer::JucerProject;
+use init_project::{detect_project, init_project, CUSTOM_TEMPLATE_NAME};
+use manifest::{load_project, read_last_build_type, save_last_build_type};
+use placeholders::{placeholder_names, Variables};
+use plugin::{
+    garage_band_warning, validate_bundle_id, validate_company_name, validate_formats,
+    validate_manufacturer_code, validate_midi, validate_plugin_code, PluginKind, PluginOptions,
+    DEFAULT_COMPANY_NAME, PLUGIN_FORMATS,
+};
 use std::error::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
-
-#[derive(Clone, Copy, ValueEnum)]
-enum Template {
-    GuiApplication,
-    AudioPlugin,
-    ConsoleApp,
-}
+use std::io::{self, IsTerminal};
+use templates::{
+    export_builtin_template, fetch_git_template, find_user_template, find_user_templates,
+    template_search_dirs, UserTemplate,
+};
+use update_juce::update_juce;
 
-impl Template {
-    fn as_str(&self) -> &'static str {
-        match self {
-            Self::GuiApplication => "GuiApplication",
-            Self::AudioPlugin => "AudioPlugin",
-            Self::ConsoleApp => "ConsoleApp",
-        }
-    }
-}
+// The built-in template used with --yes.
+const DEFAULT_TEMPLATE: &str = "GuiApplication";
 
-#[derive(Clone, Copy, ValueEnum)]
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
 enum ElementType {
     Class,
     Component,
+    Processor,
+    AudioApp,
+    LookAndFeel,
+    Thread,
+    TimerComponent,
+    AudioProcessor,
+    Parameter,
 }
 
 impl ElementType {
@@ -47,6 +70,13 @@ impl ElementType {
         match self {
             Self::Class => "class",
             Self::Component => "component",
+            Self::Processor => "processor",
+            Self::AudioApp => "audio-app",
+            Self::LookAndFeel => "look-and-feel",
+            Self::Thread => "thread",
+            Self::TimerComponent => "timer-component",
+            Self::AudioProcessor => "audio-processor",
+            Self::Parameter => "parameter",
         }
     }
 }
@@ -59,6 +89,8 @@ struct Cli {
     command: Commands,
 }
 
+// `new` carries many optional flags; boxing them would only complicate the clap derive.
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Create a new JUCE project.
@@ -69,32 +101,280 @@ enum Commands {
         /// The path to create the project at (optional).
         #[arg(short, long, name = "path")]
         path: Option<String>,
-        /// The template to use (optional).
-        #[arg(short, long, value_enum, name = "template")]
-        template: Option<Template>,
+        #[command(flatten)]
+        project: ProjectArgs,
+        /// Print the files and git operations without creating anything.
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+        /// Keep the partially created project if creation fails, for debugging.
+        #[arg(long = "keep-on-failure")]
+        keep_on_failure: bool,
     },
-    /// Add a new c++ class or a JUCE component to the project.
+    /// Turn the current directory, empty or holding a CMake project, into a JuMake project.
+    Init {
+        /// The project name (defaults to the name in CMakeLists.txt or the directory name).
+        #[arg(long = "name")]
+        name: Option<String>,
+        #[command(flatten)]
+        project: ProjectArgs,
+    },
+    /// Add a new c++ class, JUCE component, DSP processor, plugin parameter or user element to the project.
     Add {
-        /// The type of element to add (simple c++ class or JUCE component).
+        /// The type of element to add (a built-in type or a user element from .jumake/templates/elements or templates/elements in the config directory).
         #[arg(
-            value_enum,
             name = "class type",
-            help = "Specify the type of class to add, 'component' or 'class'."
+            help = "Specify the type of element to add: 'class', 'component', 'processor', 'audio-app', 'look-and-feel', 'thread', 'timer-component', 'audio-processor', 'parameter' or the name of a user element."
+        )]
+        element_type: String,
+        /// The name of the class or component, or the id of the parameter.
+        #[arg(
+            name = "name",
+            help = "Specify the name of the class or the parameter id to add. "
         )]
-        element_type: ElementType,
-        /// The name of the class or component.
-        #[arg(name = "name", help = "Specify the name of the class to add. ")]
         element_name: String,
+        /// Print the new files and the CMakeLists.txt diff without changing anything.
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+        #[command(flatten)]
+        class: ClassArgs,
+        #[command(flatten)]
+        parameter: ParameterArgs,
+    },
+    /// Create a JuMake project from a Projucer .jucer file.
+    Import {
+        /// The .jucer file.
+        #[arg(name = "jucer")]
+        jucer: PathBuf,
+        /// The project name (defaults to the name in the .jucer file).
+        #[arg(long = "name")]
+        name: Option<String>,
+        /// The path to create the project at (optional).
+        #[arg(short, long, name = "path")]
+        path: Option<String>,
+        #[command(flatten)]
+        juce: JuceArgs,
+        /// Keep the partially created project if creation fails, for debugging.
+        #[arg(long = "keep-on-failure")]
+        keep_on_failure: bool,
     },
     /// Build the project.
     Build {
-        #[arg(short = 't', long = "build-type", default_value_t = String::from("Release"))]
-        build_type: String,
+        /// The build type (defaults to the one stored in jumake.toml).
+        #[arg(short = 't', long = "build-type")]
+        build_type: Option<String>,
     },
     /// Build and Run the project.
     Run {
-        #[arg(short = 't', long = "build-type", default_value = "LastUsed")]
-        build_type: String,
+        /// The build type (defaults to the last one built, then to the one in jumake.toml).
+        #[arg(short = 't', long = "build-type")]
+        build_type: Option<String>,
+    },
+    /// Manage the JUCE version of the project.
+    Juce {
+        #[command(subcommand)]
+        command: JuceCommands,
+    },
+    /// Manage the shared JUCE cache.
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+    /// List, inspect and export project templates.
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommands,
+    },
+}
+
+// Options shared by `new` and `init`.
+#[derive(Args)]
+struct ProjectArgs {
+    /// The template to use: gui-application, audio-plugin, console-app, static-library,
+    /// juce-module, plugin-host or a user template.
+    #[arg(short, long, name = "template")]
+    template: Option<String>,
+    /// Also look for user templates in this directory.
+    #[arg(long = "template-dir", name = "template_dir")]
+    template_dir: Option<PathBuf>,
+    /// Use the template in this git repository, optionally at a tag, branch or commit (<url>[#<rev>]).
+    #[arg(
+    long = "template-git",
+    name = "template_git",
+    value_name = "URL[#REV]",
+    conflicts_with_all = ["template", "template_dir"]
+    )]
+    template_git: Option<String>,
+    /// Set a placeholder declared by a user template (repeatable).
+    #[arg(long = "var", value_name = "NAME=VALUE", value_parser = parse_variable)]
+    vars: Vec<(String, String)>,
+    #[command(flatten)]
+    juce: JuceArgs,
+    /// Never prompt; fail if a required choice was not given on the command line.
+    #[arg(long = "no-input")]
+    no_input: bool,
+    /// Use the default for every choice not given on the command line instead of prompting.
+    #[arg(short = 'y', long = "yes")]
+    yes: bool,
+    #[command(flatten)]
+    plugin: PluginArgs,
+}
+
+// Where a new project's JUCE comes from.
+#[derive(Args)]
+struct JuceArgs {
+    /// The JUCE tag, branch or commit to check out (defaults to JUCE's default branch).
+    #[arg(
+        long = "juce-version",
+        name = "juce_version",
+        conflicts_with = "juce_path"
+    )]
+    juce_version: Option<String>,
+    /// Clone JUCE from this git URL (also settable via JUMAKE_JUCE_URL).
+    #[arg(long = "juce-url", name = "juce_url", conflicts_with = "juce_path")]
+    juce_url: Option<String>,
+    /// Use an existing JUCE checkout outside the project instead of cloning it.
+    #[arg(long = "juce-path", name = "juce_path")]
+    juce_path: Option<PathBuf>,
+    /// Clone JUCE straight from its URL instead of going through the shared JUCE cache.
+    #[arg(long = "no-cache")]
+    no_cache: bool,
+}
+
+// The options of `jumake add` for classes.
+#[derive(Args)]
+struct ClassArgs {
+    /// The namespace to declare the class in, e.g. mylib::dsp.
+    #[arg(long = "namespace")]
+    namespace: Option<String>,
+    /// Generate only the header, with the definitions inline.
+    #[arg(long = "header-only", conflicts_with = "source_only")]
+    header_only: bool,
+    /// Generate only the source, declaring the class in it.
+    #[arg(long = "source-only")]
+    source_only: bool,
+    /// Also list the header in src/CMakeLists.txt so IDE projects show it.
+    #[arg(long = "list-headers", conflicts_with = "source_only")]
+    list_headers: bool,
+}
+
+// The options of `jumake add parameter`.
+#[derive(Args)]
+struct ParameterArgs {
+    /// The parameter type (for parameters).
+    #[arg(long = "type", value_enum)]
+    parameter_type: Option<ParameterType>,
+    /// The value range of float and int parameters as <min>:<max>, e.g. 20:20000.
+    #[arg(long = "range", allow_hyphen_values = true)]
+    range: Option<String>,
+    /// The default value; choices can be given by name or index.
+    #[arg(long = "default", allow_hyphen_values = true)]
+    default: Option<String>,
+    /// The choices of a choice parameter, comma separated.
+    #[arg(long = "choices", value_delimiter = ',')]
+    choices: Vec<String>,
+    /// Add an editor control attached to the parameter.
+    #[arg(long = "attach", value_enum)]
+    attach: Option<ParameterControl>,
+}
+
+impl ParameterArgs {
+    // The parameter-only flags that were given, to reject them for other element types.
+    fn given_flags(&self) -> Vec<&'static str> {
+        [
+            ("--type", self.parameter_type.is_some()),
+            ("--range", self.range.is_some()),
+            ("--default", self.default.is_some()),
+            ("--choices", !self.choices.is_empty()),
+            ("--attach", self.attach.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, given)| *given)
+        .map(|(flag, _)| flag)
+        .collect()
+    }
+}
+
+// Plugin metadata for the AudioPlugin template; anything not given is prompted for or defaulted.
+#[derive(Args)]
+struct PluginArgs {
+    /// The company name, used as the plugin's author.
+    #[arg(long = "company")]
+    company: Option<String>,
+    /// A four-character manufacturer id with at least one upper-case character.
+    #[arg(long = "manufacturer-code")]
+    manufacturer_code: Option<String>,
+    /// A unique four-character plugin id with exactly one upper-case character.
+    #[arg(long = "plugin-code")]
+    plugin_code: Option<String>,
+    /// The bundle identifier, e.g. com.yourcompany.myplugin.
+    #[arg(long = "bundle-id")]
+    bundle_id: Option<String>,
+    /// Whether the plugin is an audio effect, a synth or a MIDI effect.
+    #[arg(long = "plugin-kind", value_enum)]
+    plugin_kind: Option<PluginKind>,
+    /// Whether the plugin needs MIDI input.
+    #[arg(long = "midi-input", num_args = 0..=1, default_missing_value = "true")]
+    midi_input: Option<bool>,
+    /// Whether the plugin produces MIDI output.
+    #[arg(long = "midi-output", num_args = 0..=1, default_missing_value = "true")]
+    midi_output: Option<bool>,
+    /// The plugin formats to build, comma separated (e.g. AU,VST3,Standalone).
+    #[arg(long = "formats", value_delimiter = ',')]
+    formats: Option<Vec<String>>,
+    /// Generate an AudioProcessorValueTreeState with a parameter layout and state save/restore.
+    #[arg(long = "with-apvts")]
+    with_apvts: bool,
+}
+
+#[derive(Subcommand)]
+enum JuceCommands {
+    /// Check out another JUCE tag, branch or commit in modules/JUCE.
+    Update {
+        /// The JUCE tag, branch or commit (defaults to the revision in jumake.toml).
+        #[arg(name = "revision")]
+        revision: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TemplateCommands {
+    /// List the built-in and user templates.
+    List {
+        /// Also look for user templates in this directory.
+        #[arg(long = "template-dir", name = "template_dir")]
+        template_dir: Option<PathBuf>,
+    },
+    /// Show the files and placeholders of a template.
+    Show {
+        /// The template name.
+        #[arg(name = "name")]
+        name: String,
+        /// Also look for user templates in this directory.
+        #[arg(long = "template-dir", name = "template_dir")]
+        template_dir: Option<PathBuf>,
+    },
+    /// Write a built-in template to a directory as the starting point for a user template.
+    Export {
+        /// The built-in template name.
+        #[arg(name = "name")]
+        name: String,
+        /// The new template directory; its name becomes the template name.
+        #[arg(name = "dir")]
+        dir: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommands {
+    /// List the cached JUCE revisions.
+    List,
+    /// Fetch the latest commits of cached JUCE branches.
+    Update,
+    /// Remove cached JUCE revisions that have not been used for a while.
+    Prune {
+        /// Remove revisions not used for this many days (0 removes all of them).
+        #[arg(long = "older-than", name = "days", default_value_t = 30)]
+        older_than: u64,
     },
 }
 
@@ -105,180 +385,811 @@ fn main() {
         Commands::New {
             project_name,
             path,
-            template,
+            project,
+            dry_run,
+            keep_on_failure,
         } => {
             let project_path = match path {
                 Some(p) => PathBuf::from(p).join(&project_name),
                 None => PathBuf::from(&project_name),
             };
-            // Determine template name
-            let template_name = match template {
-                Some(t) => Some(t.as_str().to_string()),
-                None => {
-                    // Display menu and get user selection
-                    let selections = [
-                        Template::GuiApplication,
-                        Template::AudioPlugin,
-                        Template::ConsoleApp,
-                    ];
-                    let labels = ["GuiApplication", "AudioPlugin", "ConsoleApp"];
-                    let selection = Select::with_theme(&ColorfulTheme::default())
-                        .with_prompt("Select a template:")
-                        .default(0)
-                        .items(&labels[..])
-                        .interact()
-                        .expect("Failed to get template selection");
-
-                    Some(selections[selection].as_str().to_string())
+            // Keeps the checked-out git template alive until the project is created.
+            let (mut context, _template_checkout) =
+                project_context(project_name, project_path, project, None);
+            context.keep_on_failure = keep_on_failure;
+
+            if dry_run {
+                match dry_run_new(&context) {
+                    Ok(report) => print!("{}", report),
+                    Err(e) => exit_with_error(format!("Failed to create project: {}", e)),
                 }
-            };
+            } else if let Err(e) = create_project(&context) {
+                exit_with_error(format!("Failed to create project: {}", e));
+            }
+        }
+        Commands::Init { name, project } => {
+            let project_path = std::env::current_dir().unwrap_or_else(|e| {
+                exit_with_error(format!("Failed to read the current directory: {}", e))
+            });
+            let existing = detect_project(&project_path).unwrap_or_else(|e| exit_with_error(e));
+            let detected_template = existing.as_ref().map(|existing| {
+                if project.template.is_some() || project.template_git.is_some() {
+                    exit_with_error(
+                        "This directory already has a CMakeLists.txt; templates only apply to \
+                         empty directories.",
+                    );
+                }
+                existing
+                    .template_name
+                    .clone()
+                    .unwrap_or_else(|| String::from(CUSTOM_TEMPLATE_NAME))
+            });
+            let project_name = name
+                .or_else(|| existing.as_ref().and_then(|existing| existing.name.clone()))
+                .or_else(|| {
+                    project_path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                })
+                .unwrap_or_else(|| {
+                    exit_with_error("Cannot name a project after the root directory")
+                });
+            let (mut context, _template_checkout) =
+                project_context(project_name, project_path, project, detected_template);
+            if let Some(version) = existing.and_then(|existing| existing.version) {
+                context.version = version;
+            }
 
+            if let Err(e) = init_project(&context) {
+                exit_with_error(format!("Failed to initialize project: {}", e));
+            }
+        }
+        Commands::Import {
+            jucer,
+            name,
+            path,
+            juce,
+            keep_on_failure,
+        } => {
+            let project = JucerProject::load(&jucer).unwrap_or_else(|e| exit_with_error(e));
+            let project_name = name.unwrap_or_else(|| project.project_name());
+            let project_path = match path {
+                Some(p) => PathBuf::from(p).join(&project_name),
+                None => PathBuf::from(&project_name),
+            };
             let context = Context {
                 project_name,
                 project_path,
-                template_name,
-                build_type: String::from("Release"),
+                keep_on_failure,
+                ..juce_context(juce)
             };
-
-            if let Err(e) = create_project(&context) {
-                eprintln!("Failed to create project: {}", e);
+            let import = project.translate(&context).unwrap_or_else(|e| {
+                exit_with_error(format!("Failed to import {}: {}", jucer.display(), e))
+            });
+            if let Err(e) = create_project_from_files(&import.context, &import.files) {
+                exit_with_error(format!("Failed to create project: {}", e));
+            }
+            if !import.untranslated.is_empty() {
+                println!("\nCould not translate from {}:", jucer.display());
+                for item in &import.untranslated {
+                    println!("  - {}", item);
+                }
             }
         }
         Commands::Build { build_type } => {
-            if let Err(error_message) = validate_build_type(&build_type) {
-                eprintln!("{}", error_message);
-                return;
-            }
-            let project_path = std::env::current_dir().expect("Failed to get current directory");
-            let context = Context {
-                project_name: project_path
-                    .file_name()
-                    .unwrap()
-                    .to_string_lossy()
-                    .to_string(),
-                project_path: project_path.clone(),
-                template_name: determine_template_name(&project_path),
-                build_type: build_type.clone(),
-            };
+            let context = load_context(build_type).unwrap_or_else(|e| exit_with_error(e));
 
             if let Err(e) = build_project(&context) {
-                eprintln!("Build failed: {}", e);
-            } else if let Err(e) = save_build_type(&context) {
-                eprintln!("Failed to save last build type {}", e);
+                exit_with_error(format!("Build failed: {}", e));
+            } else if let Err(e) = save_last_build_type(&context) {
+                exit_with_error(format!("Failed to save last build type {}", e));
             }
         }
         Commands::Run { build_type } => {
-            let project_path = std::env::current_dir().expect("Failed to get current directory");
-            let effective_build_type = if build_type == "LastUsed" {
-                read_last_build_type(&project_path).unwrap_or_else(|| String::from("Release"))
-            } else {
-                build_type
-            };
-            if let Err(error_message) = validate_build_type(&effective_build_type) {
-                eprintln!("{}", error_message);
-                return;
+            let use_last_build_type = build_type.is_none();
+            let mut context = load_context(build_type).unwrap_or_else(|e| exit_with_error(e));
+            // Without --build-type, run what was built last.
+            if let Some(last_build_type) = read_last_build_type(&context)
+                .filter(|build_type| use_last_build_type && validate_build_type(build_type).is_ok())
+            {
+                context.build_type = last_build_type;
             }
-            let project_name = match extract_project_name(project_path.join("CMakeLists.txt")) {
-                Ok(name) => name,
-                Err(e) => {
-                    eprintln!("Failed to extract project name: {}", e);
-                    return;
-                }
-            };
-            let context = Context {
-                project_name: project_name.clone(),
-                project_path: project_path.clone(),
-                template_name: determine_template_name(&project_path),
-                build_type: effective_build_type,
-            };
             if let Err(e) = run_project(&context) {
-                eprintln!("Failed to run: {}", e);
+                exit_with_error(format!("Failed to run: {}", e));
             }
         }
         Commands::Add {
             element_type,
             element_name,
+            dry_run,
+            class,
+            parameter,
         } => {
-            let project_path = std::env::current_dir().expect("Failed to get current directory");
-            let context = Context {
-                project_name: project_path
-                    .file_name()
-                    .unwrap()
-                    .to_string_lossy()
-                    .to_string(),
-                project_path,
-                template_name: None, // We don't need the template name for this command
-                build_type: String::from("Release"), // Default build type
+            let context = load_context(None).unwrap_or_else(|e| exit_with_error(e));
+            // Built-in types are matched case-insensitively, anything else names a user element.
+            let element_type = ElementType::from_str(&element_type, true)
+                .map(|element_type| element_type.as_str().to_string())
+                .unwrap_or(element_type);
+
+            let options = ClassOptions {
+                namespace: class.namespace,
+                header_only: class.header_only,
+                source_only: class.source_only,
+                list_headers: class.list_headers,
             };
 
-            if let Err(e) = add_class(&context, element_type.as_str(), &element_name) {
-                eprintln!("Failed to add {}: {}", element_type.as_str(), e);
+            if element_type == ElementType::Parameter.as_str() {
+                if options.namespace.is_some()
+                    || options.header_only
+                    || options.source_only
+                    || options.list_headers
+                {
+                    exit_with_error("--namespace, --header-only, --source-only and --list-headers do not apply to parameters");
+                }
+                let Some(parameter_type) = parameter.parameter_type else {
+                    exit_with_error("A parameter needs --type float|bool|choice|int");
+                };
+                let spec = ParameterSpec {
+                    id: element_name,
+                    parameter_type,
+                    range: parameter.range,
+                    default: parameter.default,
+                    choices: parameter.choices,
+                    attach: parameter.attach,
+                };
+                let result = if dry_run {
+                    dry_run_add_parameter(&context, &spec).map(|report| print!("{}", report))
+                } else {
+                    add_parameter(&context, &spec)
+                };
+                if let Err(e) = result {
+                    exit_with_error(format!("Failed to add parameter: {}", e));
+                }
+            } else if !parameter.given_flags().is_empty() {
+                exit_with_error(format!(
+                    "{} only apply to parameters",
+                    parameter.given_flags().join(", ")
+                ));
+            } else if dry_run {
+                match dry_run_add(&context, &element_type, &element_name, &options) {
+                    Ok(report) => print!("{}", report),
+                    Err(e) => exit_with_error(format!("Failed to add {}: {}", element_type, e)),
+                }
+            } else if let Err(e) = add_class(&context, &element_type, &element_name, &options) {
+                exit_with_error(format!("Failed to add {}: {}", element_type, e));
+            }
+        }
+        Commands::Juce {
+            command: JuceCommands::Update { revision },
+        } => {
+            let context = load_context(None).unwrap_or_else(|e| exit_with_error(e));
+            match update_juce(&context, revision.as_deref()) {
+                Ok(update) => {
+                    println!(
+                        "JUCE updated to {}: {} ({}) -> {} ({})",
+                        update.revision,
+                        update.old_commit,
+                        update.old_version.as_deref().unwrap_or("unknown version"),
+                        update.new_commit,
+                        update.new_version.as_deref().unwrap_or("unknown version")
+                    );
+                    println!("The new JUCE revision is staged; commit it with `git commit`.");
+                }
+                Err(e) => exit_with_error(format!("Failed to update JUCE: {}", e)),
+            }
+        }
+        Commands::Cache { command } => {
+            if let Err(e) = run_cache_command(command) {
+                exit_with_error(format!("Cache command failed: {}", e));
+            }
+        }
+        Commands::Template { command } => {
+            if let Err(e) = run_template_command(command) {
+                exit_with_error(format!("Template command failed: {}", e));
             }
         } // Add other command implementations here later
     }
 }
 
-fn validate_build_type(build_type: &str) -> Result<(), String> {
-    match build_type {
-        "Debug" | "Release" | "RelWithDebInfo" | "MinSizeRel" => Ok(()),
-        _ => Err(format!(
-            "Invalid build type: {}. Use one of: Debug, Release, RelWithDebInfo, MinSizeRel",
-            build_type
-        )),
+// Print the error and exit with a non-zero status, so scripts can detect failures.
+fn exit_with_error(message: impl std::fmt::Display) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+// Build the context of a new project from the shared `new`/`init` options, prompting for what
+// is missing. `detected_template` skips the template and plugin questions for projects whose
+// CMake code already exists. The returned directory holds a template fetched with
+// --template-git and must outlive the project creation.
+fn project_context(
+    project_name: String,
+    project_path: PathBuf,
+    args: ProjectArgs,
+    detected_template: Option<String>,
+) -> (Context, Option<tempfile::TempDir>) {
+    let interactive = !args.no_input && io::stdin().is_terminal() && io::stderr().is_terminal();
+    let prompt = interactive && !args.yes;
+    let mut template_checkout = None;
+    let (template_name, template_dir, template_variables, company, plugin) = match detected_template
+    {
+        Some(template_name) => (
+            template_name,
+            None,
+            Variables::new(),
+            args.plugin.company,
+            None,
+        ),
+        None => {
+            // Determine template name
+            let search_dirs = template_search_dirs(args.template_dir.as_deref());
+            let template = match args.template_git {
+                Some(spec) => {
+                    let checkout = tempfile::tempdir().unwrap_or_else(|e| {
+                        exit_with_error(format!("Failed to create a temporary directory: {}", e))
+                    });
+                    let template = fetch_git_template(&spec, checkout.path())
+                        .unwrap_or_else(|e| exit_with_error(e));
+                    template_checkout = Some(checkout);
+                    TemplateChoice::User(template)
+                }
+                None => select_template(args.template, &search_dirs, args.yes, interactive)
+                    .unwrap_or_else(|e| exit_with_error(e)),
+            };
+            let template_variables = match &template {
+                TemplateChoice::User(user_template) => {
+                    select_template_variables(user_template, args.vars, prompt)
+                        .unwrap_or_else(|e| exit_with_error(e))
+                }
+                TemplateChoice::BuiltIn(_) => Default::default(),
+            };
+            let company = args.plugin.company.clone();
+            let plugin = if template.is_plugin() {
+                Some(
+                    select_plugin_options(args.plugin, &project_name, prompt)
+                        .unwrap_or_else(|e| exit_with_error(e)),
+                )
+            } else {
+                None
+            };
+            let company = match &plugin {
+                Some((company, _)) => Some(company.clone()),
+                None => company,
+            };
+            (
+                template.name().to_string(),
+                template.dir(),
+                template_variables,
+                company,
+                plugin.map(|(_, options)| options),
+            )
+        }
+    };
+
+    let context = Context {
+        project_name,
+        project_path,
+        template_name: Some(template_name),
+        template_dir,
+        template_variables,
+        company,
+        plugin,
+        ..juce_context(args.juce)
+    };
+    (context, template_checkout)
+}
+
+// A context with nothing but the JUCE options set.
+fn juce_context(args: JuceArgs) -> Context {
+    Context {
+        juce_revision: args.juce_version,
+        juce_source: resolve_juce_url(args.juce_url),
+        juce_path: args
+            .juce_path
+            .map(std::path::absolute)
+            .transpose()
+            .unwrap_or_else(|e| exit_with_error(format!("Invalid JUCE path: {}", e))),
+        juce_cache_dir: if args.no_cache {
+            None
+        } else {
+            default_cache_dir()
+        },
+        ..Default::default()
     }
 }
 
-fn determine_template_name(project_path: &Path) -> Option<String> {
-    let cmakelists_path = project_path.join("src").join("CMakeLists.txt");
+// A built-in template or one loaded from a user template directory.
+enum TemplateChoice {
+    BuiltIn(&'static BuiltinTemplate),
+    User(UserTemplate),
+}
 
-    if cmakelists_path.exists() {
-        // Read the CMakeLists.txt content
-        let content = fs::read_to_string(&cmakelists_path).unwrap_or_default();
+impl TemplateChoice {
+    fn name(&self) -> &str {
+        match self {
+            Self::BuiltIn(template) => template.name,
+            Self::User(template) => template.name(),
+        }
+    }
 
-        // Define a regular expression to extract the JUMAKE_TEMPLATE value
-        let re = Regex::new(r#"set\(JUMAKE_TEMPLATE\s+"([^"]+)"\)"#).unwrap();
+    fn dir(&self) -> Option<PathBuf> {
+        match self {
+            Self::BuiltIn(_) => None,
+            Self::User(template) => Some(template.path.clone()),
+        }
+    }
 
-        // Find the match
-        if let Some(captures) = re.captures(&content) {
-            // Extract the template name from the first capture group
-            return Some(captures.get(1).unwrap().as_str().to_string());
+    fn is_plugin(&self) -> bool {
+        match self {
+            Self::BuiltIn(template) => template.name == "AudioPlugin",
+            Self::User(template) => template.manifest.template.plugin,
         }
     }
+}
+
+fn default_template() -> &'static BuiltinTemplate {
+    find_builtin_template(DEFAULT_TEMPLATE).expect("The default template is built in")
+}
+
+// Pick the template from the command line, the default one with --yes, or an interactive menu.
+// Built-in templates win over user templates of the same name.
+fn select_template(
+    template: Option<String>,
+    search_dirs: &[PathBuf],
+    yes: bool,
+    interactive: bool,
+) -> Result<TemplateChoice, Box<dyn Error>> {
+    if let Some(name) = template {
+        if let Some(template) = find_builtin_template(&name) {
+            return Ok(TemplateChoice::BuiltIn(template));
+        }
+        return find_user_template(search_dirs, &name)?
+            .map(TemplateChoice::User)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown template '{}'. Built-in templates are {}; user templates are \
+                     looked up in {}.",
+                    name,
+                    BUILTIN_TEMPLATES
+                        .iter()
+                        .map(BuiltinTemplate::cli_name)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    search_dirs
+                        .iter()
+                        .map(|dir| dir.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+                .into()
+            });
+    }
+    if yes {
+        return Ok(TemplateChoice::BuiltIn(default_template()));
+    }
+    if !interactive {
+        return Err(format!(
+            "No template given and prompts are disabled (--no-input or no terminal). \
+             Pass --template <TEMPLATE>, or --yes to use the default ({}).",
+            default_template().cli_name()
+        )
+        .into());
+    }
+
+    // Display menu and get user selection
+    let mut selections: Vec<TemplateChoice> = BUILTIN_TEMPLATES
+        .iter()
+        .map(TemplateChoice::BuiltIn)
+        .collect();
+    selections.extend(
+        find_user_templates(search_dirs)?
+            .into_iter()
+            .map(TemplateChoice::User),
+    );
+    let labels: Vec<String> = selections
+        .iter()
+        .map(|choice| match choice {
+            TemplateChoice::User(template)
+                if !template.manifest.template.description.is_empty() =>
+            {
+                format!(
+                    "{} - {}",
+                    template.name(),
+                    template.manifest.template.description
+                )
+            }
+            _ => choice.name().to_string(),
+        })
+        .collect();
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Select a template:")
+        .default(0)
+        .items(&labels[..])
+        .interact()
+        .map_err(|e| format!("Failed to get template selection: {}", e))?;
 
-    // Default to GuiApplication if no template is found
-    Some("GuiApplication".to_string())
+    Ok(selections.swap_remove(selection))
 }
-fn save_build_type(context: &Context) -> std::io::Result<()> {
-    fs::write(context.project_path.join(".jumake"), &context.build_type)
+
+// Values for the placeholders a user template declares: --var wins, then a prompt (if `prompt`
+// is set), then the template's default. Placeholders without any value are an error.
+fn select_template_variables(
+    template: &UserTemplate,
+    vars: Vec<(String, String)>,
+    prompt: bool,
+) -> Result<Variables, Box<dyn Error>> {
+    let mut given: Variables = vars.into_iter().collect();
+    let mut variables = Variables::new();
+    for (name, placeholder) in &template.manifest.placeholders {
+        let value = match (given.remove(name), &placeholder.default) {
+            (Some(value), _) => value,
+            (None, default) if prompt => {
+                let label = if placeholder.description.is_empty() {
+                    name.clone()
+                } else {
+                    placeholder.description.clone()
+                };
+                let theme = ColorfulTheme::default();
+                let input = Input::with_theme(&theme).with_prompt(label);
+                match default {
+                    Some(default) => input.default(default.clone()),
+                    None => input,
+                }
+                .interact_text()
+                .map_err(|e| format!("Failed to read placeholder '{}': {}", name, e))?
+            }
+            (None, Some(default)) => default.clone(),
+            (None, None) => {
+                return Err(format!(
+                    "Template '{}' needs a value for '{}'; pass --var {}=<value>.",
+                    template.name(),
+                    name,
+                    name
+                )
+                .into())
+            }
+        };
+        variables.insert(name.clone(), value);
+    }
+    if let Some(name) = given.keys().next() {
+        return Err(format!(
+            "Template '{}' does not declare a placeholder '{}'.",
+            template.name(),
+            name
+        )
+        .into());
+    }
+    Ok(variables)
 }
 
-fn read_last_build_type(project_path: &Path) -> Option<String> {
-    fs::read_to_string(project_path.join(".jumake")).ok()
+fn parse_variable(value: &str) -> Result<(String, String), String> {
+    value
+        .split_once('=')
+        .filter(|(name, _)| !name.is_empty())
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected NAME=VALUE, got '{}'", value))
 }
 
-fn extract_project_name<P: AsRef<Path>>(cmake_file_path: P) -> Result<String, Box<dyn Error>> {
-    // Open the file
-    let file = File::open(cmake_file_path)?;
-    let reader = BufReader::new(file);
-
-    // Read the file line by line
-    for line in reader.lines() {
-        let line = line?;
-        // Look for the line that starts with "project("
-        if line.trim_start().starts_with("project(") {
-            // Extract the content between the parentheses
-            if let Some(start) = line.find('(') {
-                if let Some(end) = line.find(')') {
-                    // Get the content between the parentheses and split by whitespace
-                    let content = &line[start + 1..end];
-                    let parts: Vec<&str> = content.split_whitespace().collect();
-                    if let Some(project_name) = parts.first() {
-                        return Ok(project_name.to_string());
-                    }
+// Complete the plugin metadata given on the command line, prompting for the rest if `prompt`
+// is set and using the defaults otherwise. Returns the company name and the plugin options.
+fn select_plugin_options(
+    args: PluginArgs,
+    project_name: &str,
+    prompt: bool,
+) -> Result<(String, PluginOptions), Box<dyn Error>> {
+    let theme = ColorfulTheme::default();
+    let prompt_error = |e: dialoguer::Error| format!("Failed to read plugin metadata: {}", e);
+
+    let company = match args.company {
+        Some(company) => company,
+        None if prompt => Input::with_theme(&theme)
+            .with_prompt("Company name")
+            .default(DEFAULT_COMPANY_NAME.to_string())
+            .validate_with(|input: &String| validate_company_name(input))
+            .interact_text()
+            .map_err(prompt_error)?,
+        None => DEFAULT_COMPANY_NAME.to_string(),
+    };
+    validate_company_name(&company)?;
+    let defaults = PluginOptions::defaults(project_name, Some(&company));
+
+    let text_value = |value: Option<String>,
+                      label: &str,
+                      default: String,
+                      validate: fn(&str) -> Result<(), String>|
+     -> Result<String, Box<dyn Error>> {
+        let value = match value {
+            Some(value) => value,
+            None if prompt => Input::with_theme(&theme)
+                .with_prompt(label)
+                .default(default)
+                .validate_with(|input: &String| validate(input))
+                .interact_text()
+                .map_err(prompt_error)?,
+            None => default,
+        };
+        validate(&value)?;
+        Ok(value)
+    };
+    let manufacturer_code = text_value(
+        args.manufacturer_code,
+        "Manufacturer code",
+        defaults.manufacturer_code,
+        validate_manufacturer_code,
+    )?;
+    let plugin_code = text_value(
+        args.plugin_code,
+        "Plugin code",
+        defaults.plugin_code,
+        validate_plugin_code,
+    )?;
+    let bundle_id = text_value(
+        args.bundle_id,
+        "Bundle id",
+        defaults.bundle_id,
+        validate_bundle_id,
+    )?;
+
+    let kind = match args.plugin_kind {
+        Some(kind) => kind,
+        None if prompt => {
+            let kinds = [
+                PluginKind::Effect,
+                PluginKind::Synth,
+                PluginKind::MidiEffect,
+            ];
+            let labels = kinds.map(|kind| kind.as_str());
+            let selection = Select::with_theme(&theme)
+                .with_prompt("Plugin kind")
+                .default(0)
+                .items(&labels[..])
+                .interact()
+                .map_err(prompt_error)?;
+            kinds[selection]
+        }
+        None => defaults.kind,
+    };
+
+    let confirm = |value: Option<bool>, label: &str, default: bool| match value {
+        Some(value) => Ok(value),
+        None if prompt => Confirm::with_theme(&theme)
+            .with_prompt(label)
+            .default(default)
+            .interact()
+            .map_err(prompt_error),
+        None => Ok(default),
+    };
+    // MIDI effects always have MIDI input and output, so there is nothing to ask.
+    let (midi_input, midi_output) = if kind == PluginKind::MidiEffect {
+        let midi_input = args.midi_input.unwrap_or(true);
+        let midi_output = args.midi_output.unwrap_or(true);
+        validate_midi(kind, midi_input, midi_output)?;
+        (midi_input, midi_output)
+    } else {
+        (
+            confirm(
+                args.midi_input,
+                "Does the plugin need MIDI input?",
+                kind.default_midi_input(),
+            )?,
+            confirm(
+                args.midi_output,
+                "Does the plugin produce MIDI output?",
+                kind.default_midi_output(),
+            )?,
+        )
+    };
+
+    let formats = match args.formats {
+        Some(formats) => formats,
+        None if prompt => {
+            let checked = PLUGIN_FORMATS.map(|format| defaults.formats.iter().any(|f| f == format));
+            let selection = MultiSelect::with_theme(&theme)
+                .with_prompt("Plugin formats (space to toggle)")
+                .items(&PLUGIN_FORMATS[..])
+                .defaults(&checked[..])
+                .interact()
+                .map_err(prompt_error)?;
+            selection
+                .into_iter()
+                .map(|index| PLUGIN_FORMATS[index].to_string())
+                .collect()
+        }
+        None => defaults.formats,
+    };
+    validate_formats(&formats)?;
+
+    if let Some(warning) = garage_band_warning(&plugin_code) {
+        eprintln!("{}", warning);
+    }
+
+    Ok((
+        company,
+        PluginOptions {
+            manufacturer_code,
+            plugin_code,
+            bundle_id,
+            kind,
+            midi_input,
+            midi_output,
+            formats,
+            apvts: args.with_apvts,
+        },
+    ))
+}
+
+fn run_cache_command(command: CacheCommands) -> Result<(), Box<dyn Error>> {
+    let cache_dir = default_cache_dir().ok_or(format!(
+        "Could not determine the cache directory. Set {} to choose one.",
+        CACHE_DIR_ENV_VAR
+    ))?;
+
+    match command {
+        CacheCommands::List => {
+            let revisions = list_cached_revisions(&cache_dir)?;
+            if revisions.is_empty() {
+                println!("The JUCE cache at {} is empty.", cache_dir.display());
+            }
+            for cached in revisions {
+                let size = directory_size(&cached.path).unwrap_or(0);
+                println!(
+                    "{} {} ({}) - {:.1} MB",
+                    cached.url,
+                    cached.fetched.revision,
+                    cached.fetched.commit,
+                    size as f64 / (1024.0 * 1024.0)
+                );
+            }
+        }
+        CacheCommands::Update => {
+            let updated = update_cache(&cache_dir)?;
+            if updated.is_empty() {
+                println!("All cached JUCE revisions are up to date.");
+            }
+            for (old, new) in updated {
+                println!(
+                    "Updated {} {}: {} -> {}",
+                    new.url, new.fetched.revision, old.fetched.commit, new.fetched.commit
+                );
+            }
+        }
+        CacheCommands::Prune { older_than } => {
+            let removed = prune_cache(&cache_dir, older_than)?;
+            for cached in &removed {
+                println!("Removed {} {}", cached.url, cached.fetched.revision);
+            }
+            println!("Pruned {} cached JUCE revision(s).", removed.len());
+        }
+    }
+    Ok(())
+}
+
+fn run_template_command(command: TemplateCommands) -> Result<(), Box<dyn Error>> {
+    match command {
+        TemplateCommands::List { template_dir } => {
+            println!("Built-in templates:");
+            for template in &BUILTIN_TEMPLATES {
+                println!("  {:<20} {}", template.name, template.description);
+            }
+            let search_dirs = template_search_dirs(template_dir.as_deref());
+            let user_templates = find_user_templates(&search_dirs)?;
+            if !user_templates.is_empty() {
+                println!("User templates:");
+            }
+            for template in user_templates {
+                println!(
+                    "  {:<20} {} ({})",
+                    template.name(),
+                    template.manifest.template.description,
+                    template.path.display()
+                );
+            }
+        }
+        TemplateCommands::Show { name, template_dir } => {
+            if let Some(template) = find_builtin_template(&name) {
+                println!("{} (built-in): {}", template.name, template.description);
+                println!("Files:");
+                let mut placeholders = BTreeSet::new();
+                for (file_name, content) in template.files {
+                    println!("  {}", file_name);
+                    placeholders.extend(placeholder_names(&String::from_utf8_lossy(content)));
+                }
+                print_placeholders(&placeholders, None);
+                return Ok(());
+            }
+
+            let search_dirs = template_search_dirs(template_dir.as_deref());
+            let template = find_user_template(&search_dirs, &name)?
+                .ok_or(format!("Unknown template '{}'", name))?;
+            println!(
+                "{} ({}): {}",
+                template.name(),
+                template.path.display(),
+                template.manifest.template.description
+            );
+            println!("Files:");
+            let mut placeholders = BTreeSet::new();
+            for file in &template.manifest.files {
+                let source = file.source.to_string_lossy().replace('\\', "/");
+                let target = file.target_pattern();
+                let copied = if file.render { "" } else { " (copied as is)" };
+                if source == target {
+                    println!("  {}{}", target, copied);
+                } else {
+                    println!("  {} -> {}{}", source, target, copied);
+                }
+                placeholders.extend(placeholder_names(&target));
+                if file.render {
+                    let content = fs::read_to_string(template.path.join(&file.source))?;
+                    placeholders.extend(placeholder_names(&content));
                 }
             }
+            placeholders.extend(template.manifest.placeholders.keys().cloned());
+            print_placeholders(&placeholders, Some(&template));
+        }
+        TemplateCommands::Export { name, dir } => {
+            let template = find_builtin_template(&name).ok_or(format!(
+                "Unknown built-in template '{}'. Built-in templates are: {}",
+                name,
+                BUILTIN_TEMPLATES.map(|template| template.name).join(", ")
+            ))?;
+            let exported = export_builtin_template(template, &dir)?;
+            println!(
+                "Exported {} to {} as template '{}'. Edit template.toml and the files, then use it \
+                 with `jumake new <name> --template {} --template-dir {}`.",
+                template.name,
+                dir.display(),
+                exported.name(),
+                exported.name(),
+                dir.parent()
+                    .filter(|parent| !parent.as_os_str().is_empty())
+                    .unwrap_or(Path::new("."))
+                    .display()
+            );
         }
     }
+    Ok(())
+}
+
+// Print the placeholders a template uses, with the description and default of the ones
+// `template` declares itself.
+fn print_placeholders(placeholders: &BTreeSet<String>, template: Option<&UserTemplate>) {
+    println!("Placeholders:");
+    for name in placeholders {
+        let declared = template.and_then(|template| template.manifest.placeholders.get(name));
+        match declared {
+            Some(placeholder) => println!(
+                "  {{{{{}}}}} {}{}",
+                name,
+                placeholder.description,
+                placeholder
+                    .default
+                    .as_ref()
+                    .map(|default| format!(" (default: {})", default))
+                    .unwrap_or_default()
+            ),
+            None => println!("  {{{{{}}}}}", name),
+        }
+    }
+}
+
+fn validate_build_type(build_type: &str) -> Result<(), String> {
+    match build_type {
+        "Debug" | "Release" | "RelWithDebInfo" | "MinSizeRel" => Ok(()),
+        _ => Err(format!(
+            "Invalid build type: {}. Use one of: Debug, Release, RelWithDebInfo, MinSizeRel",
+            build_type
+        )),
+    }
+}
 
-    Err("Project name not found".into())
+// Load the project in the current directory into a Context, overriding the
+// manifest's default build type if one was given on the command line.
+fn load_context(build_type: Option<String>) -> Result<Context, Box<dyn Error>> {
+    let project_path = std::env::current_dir()?;
+    let mut context = load_project(&project_path)?.into_context(project_path);
+    if let Some(build_type) = build_type {
+        context.build_type = build_type;
+    }
+    validate_build_type(&context.build_type)?;
+    Ok(context)
 }
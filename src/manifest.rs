@@ -0,0 +1,257 @@
+// src/manifest.rs
+use crate::context::{Context, DEFAULT_BUILD_DIR, DEFAULT_JUCE_URL, DEFAULT_PROJECT_VERSION};
+use crate::plugin::PluginOptions;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE_NAME: &str = "jumake.toml";
+const LEGACY_STATE_FILE_NAME: &str = ".jumake";
+const LAST_BUILD_TYPE_FILE_NAME: &str = ".jumake_last_build_type";
+
+// The project manifest (jumake.toml) written by `jumake new` and read by every other command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub project: ProjectSection,
+    #[serde(default)]
+    pub juce: JuceSection,
+    #[serde(default)]
+    pub build: BuildSection,
+    // Only present in AudioPlugin projects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<PluginOptions>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectSection {
+    pub name: String,
+    pub template: String,
+    #[serde(default = "default_version")]
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub company: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JuceSection {
+    #[serde(default = "default_juce_source")]
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    // An existing JUCE checkout outside the project, used instead of modules/JUCE.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildSection {
+    #[serde(default = "default_build_type")]
+    pub default_type: String,
+    #[serde(default = "default_build_dir")]
+    pub directory: String,
+}
+
+impl Default for JuceSection {
+    fn default() -> Self {
+        JuceSection {
+            source: default_juce_source(),
+            revision: None,
+            path: None,
+        }
+    }
+}
+
+impl Default for BuildSection {
+    fn default() -> Self {
+        BuildSection {
+            default_type: default_build_type(),
+            directory: default_build_dir(),
+        }
+    }
+}
+
+fn default_version() -> String {
+    String::from(DEFAULT_PROJECT_VERSION)
+}
+
+fn default_juce_source() -> String {
+    String::from(DEFAULT_JUCE_URL)
+}
+
+fn default_build_type() -> String {
+    String::from("Release")
+}
+
+fn default_build_dir() -> String {
+    String::from(DEFAULT_BUILD_DIR)
+}
+
+impl Manifest {
+    pub fn from_context(context: &Context) -> Self {
+        Manifest {
+            project: ProjectSection {
+                name: context.project_name.clone(),
+                template: context
+                    .template_name
+                    .clone()
+                    .unwrap_or_else(|| String::from("GuiApplication")),
+                version: context.version.clone(),
+                company: context.company.clone(),
+            },
+            juce: JuceSection {
+                source: context.juce_source.clone(),
+                revision: context.juce_revision.clone(),
+                path: context.juce_path.clone(),
+            },
+            build: BuildSection {
+                default_type: context.build_type.clone(),
+                directory: context.build_dir.clone(),
+            },
+            plugin: context.plugin.clone(),
+        }
+    }
+
+    pub fn into_context(self, project_path: PathBuf) -> Context {
+        Context {
+            project_name: self.project.name,
+            project_path,
+            template_name: Some(self.project.template),
+            build_type: self.build.default_type,
+            version: self.project.version,
+            company: self.project.company,
+            juce_source: self.juce.source,
+            juce_revision: self.juce.revision,
+            juce_path: self.juce.path,
+            build_dir: self.build.directory,
+            plugin: self.plugin,
+            ..Default::default()
+        }
+    }
+
+    pub fn load(project_path: &Path) -> Result<Self, Box<dyn Error>> {
+        let manifest_path = project_path.join(MANIFEST_FILE_NAME);
+        let content = fs::read_to_string(&manifest_path)?;
+        toml::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", manifest_path.display(), e).into())
+    }
+
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(self)
+    }
+
+    pub fn save(&self, project_path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(project_path.join(MANIFEST_FILE_NAME), self.to_toml()?)?;
+        Ok(())
+    }
+}
+
+// Load the manifest of the project at `project_path`, migrating legacy projects
+// (a `.jumake` state file plus the JUMAKE_TEMPLATE marker) on first use.
+pub fn load_project(project_path: &Path) -> Result<Manifest, Box<dyn Error>> {
+    if project_path.join(MANIFEST_FILE_NAME).exists() {
+        return Manifest::load(project_path);
+    }
+    migrate_legacy_project(project_path)
+}
+
+fn migrate_legacy_project(project_path: &Path) -> Result<Manifest, Box<dyn Error>> {
+    let cmakelists_path = project_path.join("CMakeLists.txt");
+    if !cmakelists_path.exists() {
+        return Err(format!(
+            "No {} or CMakeLists.txt found in {}. Is this a JuMake project?",
+            MANIFEST_FILE_NAME,
+            project_path.display()
+        )
+        .into());
+    }
+
+    let content = fs::read_to_string(&cmakelists_path)?;
+    let (name, version) = extract_project_name_and_version(&content)
+        .ok_or("Project name not found in CMakeLists.txt")?;
+
+    let manifest = Manifest {
+        project: ProjectSection {
+            name,
+            template: determine_template_name(project_path),
+            version: version.unwrap_or_else(default_version),
+            company: None,
+        },
+        juce: JuceSection::default(),
+        build: BuildSection::default(),
+        plugin: None,
+    };
+
+    manifest.save(project_path)?;
+    // The legacy state file only held the last build type; keep it where `run` looks for it.
+    let legacy_state_path = project_path.join(LEGACY_STATE_FILE_NAME);
+    if let Some(build_type) = read_build_type(&legacy_state_path) {
+        let build_dir = project_path.join(&manifest.build.directory);
+        fs::create_dir_all(&build_dir)?;
+        fs::write(build_dir.join(LAST_BUILD_TYPE_FILE_NAME), build_type)?;
+    }
+    if legacy_state_path.is_file() {
+        fs::remove_file(legacy_state_path)?;
+    }
+    println!(
+        "Migrated legacy project '{}' to {}.",
+        manifest.project.name, MANIFEST_FILE_NAME
+    );
+    Ok(manifest)
+}
+
+fn determine_template_name(project_path: &Path) -> String {
+    let cmakelists_path = project_path.join("src").join("CMakeLists.txt");
+    let content = fs::read_to_string(cmakelists_path).unwrap_or_default();
+
+    // Extract the JUMAKE_TEMPLATE value, defaulting to GuiApplication if no marker is found.
+    let re = Regex::new(r#"set\(JUMAKE_TEMPLATE\s+"([^"]+)"\)"#).unwrap();
+    re.captures(&content)
+        .map(|captures| captures[1].to_string())
+        .unwrap_or_else(|| String::from("GuiApplication"))
+}
+
+// The build type of the last `jumake build`. It is kept in the build directory, which is not
+// tracked by git, so building never changes jumake.toml.
+pub fn read_last_build_type(context: &Context) -> Option<String> {
+    read_build_type(
+        &context
+            .project_path
+            .join(&context.build_dir)
+            .join(LAST_BUILD_TYPE_FILE_NAME),
+    )
+}
+
+pub fn save_last_build_type(context: &Context) -> Result<(), Box<dyn Error>> {
+    let build_dir = context.project_path.join(&context.build_dir);
+    fs::create_dir_all(&build_dir)?;
+    fs::write(
+        build_dir.join(LAST_BUILD_TYPE_FILE_NAME),
+        &context.build_type,
+    )?;
+    Ok(())
+}
+
+fn read_build_type(path: &Path) -> Option<String> {
+    let build_type = fs::read_to_string(path).ok()?;
+    let build_type = build_type.trim();
+    (!build_type.is_empty()).then(|| build_type.to_string())
+}
+
+pub fn extract_project_name_and_version(content: &str) -> Option<(String, Option<String>)> {
+    // Look for the line that starts with "project(" and split its arguments by whitespace.
+    let line = content
+        .lines()
+        .find(|line| line.trim_start().starts_with("project("))?;
+    let start = line.find('(')?;
+    let end = line.find(')')?;
+    let parts: Vec<&str> = line[start + 1..end].split_whitespace().collect();
+    let name = parts.first()?.to_string();
+    let version = parts
+        .iter()
+        .position(|part| *part == "VERSION")
+        .and_then(|idx| parts.get(idx + 1))
+        .map(|version| version.to_string());
+    Some((name, version))
+}
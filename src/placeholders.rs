@@ -0,0 +1,141 @@
+// src/placeholders.rs
+use crate::context::Context;
+use crate::plugin::{PluginKind, PluginOptions, DEFAULT_COMPANY_NAME};
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// The values of the `{{name}}` placeholders a template can use.
+pub type Variables = BTreeMap<String, String>;
+
+// Replace every `{{name}}` (whitespace inside the braces is allowed) with its value.
+// `source` names the template in error messages; all unknown placeholders are reported at once.
+pub fn render(template: &str, variables: &Variables, source: &str) -> Result<String, String> {
+    let re = placeholder_regex();
+
+    let mut unknown = Vec::new();
+    for captures in re.captures_iter(template) {
+        let name = &captures[1];
+        if !variables.contains_key(name) {
+            let line = template[..captures.get(0).unwrap().start()]
+                .matches('\n')
+                .count()
+                + 1;
+            unknown.push(format!("{{{{{}}}}} (line {})", name, line));
+        }
+    }
+    if !unknown.is_empty() {
+        return Err(format!(
+            "Unknown placeholder(s) in {}: {}. Known placeholders are: {}",
+            source,
+            unknown.join(", "),
+            variables.keys().cloned().collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    Ok(re
+        .replace_all(template, |captures: &regex::Captures| {
+            variables[&captures[1]].clone()
+        })
+        .into_owned())
+}
+
+// The names of all placeholders used in `template`.
+pub fn placeholder_names(template: &str) -> BTreeSet<String> {
+    placeholder_regex()
+        .captures_iter(template)
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap()
+}
+
+// The placeholders available to every template of a project.
+pub fn project_variables(context: &Context) -> Variables {
+    let company = context.company.as_deref().unwrap_or(DEFAULT_COMPANY_NAME);
+    let mut variables = Variables::new();
+    variables.insert("project_name".into(), context.project_name.clone());
+    variables.insert("project_version".into(), context.version.clone());
+    variables.insert("company".into(), company.to_string());
+    variables.insert("year".into(), current_year().to_string());
+    variables.insert(
+        "namespace".into(),
+        identifier(&context.project_name).to_lowercase(),
+    );
+
+    for (name, value) in &context.template_variables {
+        variables
+            .entry(name.clone())
+            .or_insert_with(|| value.clone());
+    }
+
+    if context.plugin.is_some() || context.template_name.as_deref() == Some("AudioPlugin") {
+        let plugin = context.plugin.clone().unwrap_or_else(|| {
+            PluginOptions::defaults(&context.project_name, context.company.as_deref())
+        });
+        insert_plugin_variables(&mut variables, &plugin);
+    }
+    variables
+}
+
+// The project's placeholders plus `{{class_name}}`, for the templates of `jumake add`.
+pub fn class_variables(context: &Context, class_name: &str) -> Variables {
+    let mut variables = project_variables(context);
+    variables.insert("class_name".into(), class_name.to_string());
+    variables
+}
+
+fn insert_plugin_variables(variables: &mut Variables, plugin: &PluginOptions) {
+    let cmake_bool = |value: bool| String::from(if value { "TRUE" } else { "FALSE" });
+    variables.insert("bundle_id".into(), plugin.bundle_id.clone());
+    variables.insert(
+        "is_synth".into(),
+        cmake_bool(plugin.kind == PluginKind::Synth),
+    );
+    variables.insert(
+        "is_midi_effect".into(),
+        cmake_bool(plugin.kind == PluginKind::MidiEffect),
+    );
+    variables.insert("needs_midi_input".into(), cmake_bool(plugin.midi_input));
+    variables.insert("needs_midi_output".into(), cmake_bool(plugin.midi_output));
+    variables.insert("manufacturer_code".into(), plugin.manufacturer_code.clone());
+    variables.insert("plugin_code".into(), plugin.plugin_code.clone());
+    variables.insert("formats".into(), plugin.formats.join(" "));
+}
+
+// Turn a project name into a valid C++ identifier, e.g. "My Synth 2" -> "My_Synth_2".
+fn identifier(name: &str) -> String {
+    let identifier: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if identifier.is_empty() || identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", identifier)
+    } else {
+        identifier
+    }
+}
+
+// The current year in UTC, from the days since the UNIX epoch (Howard Hinnant's civil_from_days).
+fn current_year() -> i64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0) as i64;
+    let days = seconds / 86_400 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let year = year_of_era + era * 400;
+    // The computed year starts in March, so January and February belong to the next one.
+    if month_index >= 10 {
+        year + 1
+    } else {
+        year
+    }
+}
@@ -0,0 +1,252 @@
+// src/plugin.rs
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_COMPANY_NAME: &str = "yourcompany";
+pub const DEFAULT_PLUGIN_FORMATS: [&str; 3] = ["AU", "VST3", "Standalone"];
+pub const PLUGIN_FORMATS: [&str; 8] = [
+    "AAX",
+    "AU",
+    "AUv3",
+    "LV2",
+    "Standalone",
+    "Unity",
+    "VST",
+    "VST3",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginKind {
+    Effect,
+    Synth,
+    MidiEffect,
+}
+
+impl PluginKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Effect => "effect",
+            Self::Synth => "synth",
+            Self::MidiEffect => "midi-effect",
+        }
+    }
+
+    // Synths play incoming notes; MIDI effects transform them and pass them on.
+    pub fn default_midi_input(&self) -> bool {
+        *self != Self::Effect
+    }
+
+    pub fn default_midi_output(&self) -> bool {
+        *self == Self::MidiEffect
+    }
+}
+
+// The metadata passed to juce_add_plugin for AudioPlugin projects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginOptions {
+    pub manufacturer_code: String,
+    pub plugin_code: String,
+    pub bundle_id: String,
+    pub kind: PluginKind,
+    pub midi_input: bool,
+    pub midi_output: bool,
+    pub formats: Vec<String>,
+    // Whether the processor gets an AudioProcessorValueTreeState with state save/restore.
+    #[serde(default)]
+    pub apvts: bool,
+}
+
+impl PluginOptions {
+    // Options for a plugin that was not configured explicitly: codes derived from the
+    // company and project name, an effect without MIDI, built as AU, VST3 and Standalone.
+    pub fn defaults(project_name: &str, company: Option<&str>) -> Self {
+        let company = company.unwrap_or(DEFAULT_COMPANY_NAME);
+        PluginOptions {
+            manufacturer_code: default_manufacturer_code(company),
+            plugin_code: default_plugin_code(project_name),
+            bundle_id: default_bundle_id(project_name, company),
+            kind: PluginKind::Effect,
+            midi_input: false,
+            midi_output: false,
+            formats: DEFAULT_PLUGIN_FORMATS.map(String::from).to_vec(),
+            apvts: false,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        validate_manufacturer_code(&self.manufacturer_code)?;
+        validate_plugin_code(&self.plugin_code)?;
+        validate_bundle_id(&self.bundle_id)?;
+        validate_midi(self.kind, self.midi_input, self.midi_output)?;
+        validate_formats(&self.formats)
+    }
+}
+
+// A MIDI effect receives MIDI and produces MIDI; it has no audio to work on otherwise.
+pub fn validate_midi(kind: PluginKind, midi_input: bool, midi_output: bool) -> Result<(), String> {
+    if kind == PluginKind::MidiEffect && !(midi_input && midi_output) {
+        return Err(String::from(
+            "A midi-effect plugin needs both MIDI input and MIDI output",
+        ));
+    }
+    Ok(())
+}
+
+// A four-character manufacturer id with at least one upper-case character.
+pub fn validate_manufacturer_code(code: &str) -> Result<(), String> {
+    validate_four_character_code("Manufacturer code", code)?;
+    if !code.chars().any(|c| c.is_ascii_uppercase()) {
+        return Err(format!(
+            "Manufacturer code '{}' must contain at least one upper-case character",
+            code
+        ));
+    }
+    Ok(())
+}
+
+// A unique four-character plugin id with exactly one upper-case character.
+pub fn validate_plugin_code(code: &str) -> Result<(), String> {
+    validate_four_character_code("Plugin code", code)?;
+    if code.chars().filter(|c| c.is_ascii_uppercase()).count() != 1 {
+        return Err(format!(
+            "Plugin code '{}' must contain exactly one upper-case character",
+            code
+        ));
+    }
+    Ok(())
+}
+
+// GarageBand 10.3 requires the first letter of the plugin code to be upper-case and the
+// remaining letters to be lower-case; other hosts do not care, so this is only a warning.
+pub fn garage_band_warning(code: &str) -> Option<String> {
+    let mut chars = code.chars();
+    let first_upper = chars.next().is_some_and(|c| c.is_ascii_uppercase());
+    let rest_lower = chars.all(|c| !c.is_ascii_uppercase());
+    (!(first_upper && rest_lower)).then(|| {
+        format!(
+            "Warning: GarageBand 10.3 requires plugin codes like 'Abcd'; '{}' may not load there.",
+            code
+        )
+    })
+}
+
+// The company name is written into a quoted CMake string, which these characters would break.
+pub fn validate_company_name(company: &str) -> Result<(), String> {
+    if company.contains(['"', '\\', '\n', '\r']) {
+        return Err(format!(
+            "Company name '{}' must not contain quotes, backslashes or line breaks",
+            company.escape_debug()
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_bundle_id(bundle_id: &str) -> Result<(), String> {
+    let valid = !bundle_id.is_empty()
+        && bundle_id.split('.').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        });
+    if !valid {
+        return Err(format!(
+            "Bundle id '{}' must look like com.company.product",
+            bundle_id
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_formats(formats: &[String]) -> Result<(), String> {
+    if formats.is_empty() {
+        return Err(String::from("At least one plugin format is required"));
+    }
+    for format in formats {
+        if !PLUGIN_FORMATS.contains(&format.as_str()) {
+            return Err(format!(
+                "Unknown plugin format '{}'. Valid formats are: {}",
+                format,
+                PLUGIN_FORMATS.join(" ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_four_character_code(label: &str, code: &str) -> Result<(), String> {
+    if code.len() != 4 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!(
+            "{} '{}' must be exactly four ASCII letters or digits",
+            label, code
+        ));
+    }
+    Ok(())
+}
+
+fn alphanumeric(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect()
+}
+
+// E.g. "Acme Audio" -> "Acme"; padded with 'x' when the company name is too short.
+fn default_manufacturer_code(company: &str) -> String {
+    let company = alphanumeric(company);
+    if company.is_empty() {
+        return String::from("Manu");
+    }
+    // An upper-case character is required; a leading digit cannot provide it.
+    let company = if company.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("M{}", company)
+    } else {
+        company
+    };
+    let mut code: String = company.chars().take(4).collect();
+    while code.len() < 4 {
+        code.push('x');
+    }
+    capitalize(&code)
+}
+
+// E.g. "MyPlugin" -> "Mypl"; padded with '0' when the project name is too short.
+fn default_plugin_code(project_name: &str) -> String {
+    let mut code: String = alphanumeric(project_name).chars().take(4).collect();
+    while code.len() < 4 {
+        code.push('0');
+    }
+    let code = capitalize(&code);
+    if code.starts_with(|c: char| c.is_ascii_digit()) {
+        // Exactly one upper-case character is required; a leading digit cannot provide it.
+        format!("P{}", &code[1..])
+    } else {
+        code
+    }
+}
+
+fn default_bundle_id(project_name: &str, company: &str) -> String {
+    let company = alphanumeric(company).to_lowercase();
+    let company = if company.is_empty() {
+        String::from(DEFAULT_COMPANY_NAME)
+    } else {
+        company
+    };
+    let product = alphanumeric(project_name).to_lowercase();
+    let product = if product.is_empty() {
+        String::from("plugin")
+    } else {
+        product
+    };
+    format!("com.{}.{}", company, product)
+}
+
+fn capitalize(value: &str) -> String {
+    let lower = value.to_ascii_lowercase();
+    let mut chars = lower.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => lower,
+    }
+}
@@ -0,0 +1,522 @@
+// src/templates.rs
+use crate::create_files::{BuiltinTemplate, GeneratedFile};
+use crate::initialize_git::fetch_revision;
+use crate::placeholders::{render, Variables};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+pub const TEMPLATE_MANIFEST_FILE_NAME: &str = "template.toml";
+pub const ELEMENT_MANIFEST_FILE_NAME: &str = "element.toml";
+pub const CONFIG_DIR_ENV_VAR: &str = "JUMAKE_CONFIG_DIR";
+pub const PROJECT_ELEMENTS_DIR: &str = ".jumake/templates/elements";
+const TEMPLATE_CMAKELISTS: &str = "src/CMakeLists.txt";
+
+// The template.toml of a user template, e.g.
+//
+//     [template]
+//     name = "InHousePlugin"
+//     description = "Our plugin skeleton"
+//     plugin = true
+//
+//     [[files]]
+//     source = "src/CMakeLists.txt"
+//
+//     [[files]]
+//     source = "Processor.cpp"
+//     target = "src/{{project_name}}Processor.cpp"
+//
+//     [placeholders.author]
+//     description = "Who maintains the plugin"
+//     default = "Audio Team"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateManifest {
+    pub template: TemplateSection,
+    #[serde(default)]
+    pub files: Vec<TemplateFile>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub placeholders: BTreeMap<String, Placeholder>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateSection {
+    // Recorded as JUMAKE_TEMPLATE in src/CMakeLists.txt and as the template in jumake.toml.
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    // Whether `jumake new` asks for plugin metadata and provides the plugin placeholders.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub plugin: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateFile {
+    // The file inside the template directory.
+    pub source: PathBuf,
+    // Where the file goes inside the project (may use placeholders); defaults to `source`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    // Set to false to copy a file (e.g. an image) without rendering placeholders.
+    #[serde(default = "default_render", skip_serializing_if = "is_true")]
+    pub render: bool,
+}
+
+// A placeholder a template adds on top of the built-in ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Placeholder {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+// A project template loaded from a directory holding a template.toml.
+#[derive(Debug, Clone)]
+pub struct UserTemplate {
+    pub path: PathBuf,
+    pub manifest: TemplateManifest,
+}
+
+// The element.toml of a user `add` element, e.g.
+//
+//     [element]
+//     name = "widget"
+//     description = "A component drawn by our LookAndFeel"
+//     suffix = "Widget"
+//     modules = ["juce::juce_gui_basics"]
+//
+//     [files]
+//     header = "Widget.h"
+//     source = "Widget.cpp"
+//     test = "WidgetTest.cpp"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElementManifest {
+    pub element: ElementSection,
+    pub files: ElementFiles,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElementSection {
+    // The kind passed to `jumake add <kind> <Name>`.
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    // Appended to the class name unless it already ends with it.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub suffix: String,
+    // The JUCE modules linked to the project when the element is added.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modules: Vec<String>,
+}
+
+// The templates of an element, relative to its directory. The header and source become
+// src/<ClassName>.h and src/<ClassName>.cpp, the test tests/<ClassName>Test.cpp.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElementFiles {
+    pub header: PathBuf,
+    pub source: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test: Option<PathBuf>,
+}
+
+// An `add` element loaded from a directory holding an element.toml.
+#[derive(Debug, Clone)]
+pub struct UserElement {
+    pub path: PathBuf,
+    pub manifest: ElementManifest,
+}
+
+fn default_render() -> bool {
+    true
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+impl UserTemplate {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let manifest_path = path.join(TEMPLATE_MANIFEST_FILE_NAME);
+        let content = fs::read_to_string(&manifest_path)
+            .map_err(|e| format!("Failed to read {}: {}", manifest_path.display(), e))?;
+        let manifest: TemplateManifest = toml::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", manifest_path.display(), e))?;
+
+        let template = UserTemplate {
+            path: path.to_path_buf(),
+            manifest,
+        };
+        template
+            .validate()
+            .map_err(|e| format!("Invalid template at {}: {}", path.display(), e))?;
+        Ok(template)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.manifest.template.name
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let name = self.name();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Template name '{}' may only contain letters, digits, '-' and '_'",
+                name
+            ));
+        }
+
+        let mut has_cmakelists = false;
+        for file in &self.manifest.files {
+            if !is_relative_inside(&file.source) || !self.path.join(&file.source).is_file() {
+                return Err(format!(
+                    "Template file '{}' does not exist in the template directory",
+                    file.source.display()
+                ));
+            }
+            let target = file.target_pattern();
+            if !is_relative_inside(Path::new(&target)) {
+                return Err(format!(
+                    "Target '{}' must be a relative path inside the project",
+                    target
+                ));
+            }
+            has_cmakelists |= target == TEMPLATE_CMAKELISTS;
+        }
+        if !has_cmakelists {
+            return Err(format!(
+                "A template must provide {} (the root CMakeLists.txt adds the src directory)",
+                TEMPLATE_CMAKELISTS
+            ));
+        }
+        Ok(())
+    }
+
+    // Render the files of the template without writing them. Placeholders declared by the
+    // template that are missing from `variables` fall back to their defaults.
+    pub fn generate(&self, variables: &Variables) -> Result<Vec<GeneratedFile>, Box<dyn Error>> {
+        let mut variables = variables.clone();
+        for (name, placeholder) in &self.manifest.placeholders {
+            if !variables.contains_key(name) {
+                let default = placeholder.default.clone().ok_or(format!(
+                    "No value for placeholder '{}' of template '{}'",
+                    name,
+                    self.name()
+                ))?;
+                variables.insert(name.clone(), default);
+            }
+        }
+
+        let mut files = Vec::new();
+        for file in &self.manifest.files {
+            let target = render(&file.target_pattern(), &variables, "template.toml")?;
+            // Placeholder values must not move a file out of the project, e.g. "../../.bashrc".
+            if !is_relative_inside(Path::new(&target)) {
+                return Err(format!(
+                    "Target '{}' of {} must be a relative path inside the project",
+                    target,
+                    file.source.display()
+                )
+                .into());
+            }
+            let source_path = self.path.join(&file.source);
+            let content = if file.render {
+                let source = file.source.to_string_lossy();
+                let mut content = render(&fs::read_to_string(&source_path)?, &variables, &source)?;
+                if target == TEMPLATE_CMAKELISTS && !content.contains("JUMAKE_TEMPLATE") {
+                    content = format!("set(JUMAKE_TEMPLATE \"{}\")\n{}", self.name(), content);
+                }
+                content.into_bytes()
+            } else {
+                fs::read(&source_path)?
+            };
+            files.push(GeneratedFile {
+                path: PathBuf::from(target),
+                content,
+            });
+        }
+        Ok(files)
+    }
+}
+
+impl UserElement {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let manifest_path = path.join(ELEMENT_MANIFEST_FILE_NAME);
+        let content = fs::read_to_string(&manifest_path)
+            .map_err(|e| format!("Failed to read {}: {}", manifest_path.display(), e))?;
+        let manifest: ElementManifest = toml::from_str(&content)
+            .map_err(|e| format!("Invalid {}: {}", manifest_path.display(), e))?;
+
+        let element = UserElement {
+            path: path.to_path_buf(),
+            manifest,
+        };
+        element
+            .validate()
+            .map_err(|e| format!("Invalid element at {}: {}", path.display(), e))?;
+        Ok(element)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.manifest.element.name
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let name = self.name();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Element name '{}' may only contain letters, digits, '-' and '_'",
+                name
+            ));
+        }
+
+        let files = &self.manifest.files;
+        for file in [&files.header, &files.source]
+            .into_iter()
+            .chain(files.test.as_ref())
+        {
+            if !is_relative_inside(file) || !self.path.join(file).is_file() {
+                return Err(format!(
+                    "Element file '{}' does not exist in the element directory",
+                    file.display()
+                ));
+            }
+        }
+        for module in &self.manifest.element.modules {
+            if !module.starts_with("juce::") {
+                return Err(format!(
+                    "Module '{}' must be a JUCE target such as juce::juce_gui_basics",
+                    module
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TemplateFile {
+    // The target path before rendering its placeholders.
+    pub fn target_pattern(&self) -> String {
+        match &self.target {
+            Some(target) => target.clone(),
+            None => self.source.to_string_lossy().replace('\\', "/"),
+        }
+    }
+}
+
+// Write the files of a built-in template and a matching template.toml to `dir`, as the starting
+// point of a user template. The new template is named after the directory.
+pub fn export_builtin_template(
+    template: &BuiltinTemplate,
+    dir: &Path,
+) -> Result<UserTemplate, Box<dyn Error>> {
+    if dir.exists() && fs::read_dir(dir)?.next().is_some() {
+        return Err(format!("{} already exists and is not empty", dir.display()).into());
+    }
+    let name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or(format!("Cannot name a template after {}", dir.display()))?;
+
+    for (file_name, content) in template.files {
+        let path = dir.join(file_name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Projects made from the export identify as the new template, not the built-in one.
+        if *file_name == TEMPLATE_CMAKELISTS {
+            let content = String::from_utf8_lossy(content).replace(
+                &format!("set(JUMAKE_TEMPLATE \"{}\")", template.name),
+                &format!("set(JUMAKE_TEMPLATE \"{}\")", name),
+            );
+            fs::write(&path, content)?;
+        } else {
+            fs::write(&path, content)?;
+        }
+    }
+
+    let manifest = TemplateManifest {
+        template: TemplateSection {
+            name,
+            description: format!("Based on the built-in {} template", template.name),
+            plugin: template.name == "AudioPlugin",
+        },
+        files: template
+            .files
+            .iter()
+            .map(|(file_name, _)| TemplateFile {
+                source: PathBuf::from(file_name),
+                target: None,
+                render: true,
+            })
+            .collect(),
+        placeholders: BTreeMap::new(),
+    };
+    fs::write(
+        dir.join(TEMPLATE_MANIFEST_FILE_NAME),
+        toml::to_string_pretty(&manifest)?,
+    )?;
+    UserTemplate::load(dir)
+}
+
+// Check out the template repository `spec` (`<url>[#<tag|branch|commit>]`) into `checkout_dir`
+// and load it. The repository's history is removed, so only the template files remain.
+pub fn fetch_git_template(spec: &str, checkout_dir: &Path) -> Result<UserTemplate, Box<dyn Error>> {
+    let (url, revision) = match spec.rsplit_once('#') {
+        Some((url, revision)) if !revision.is_empty() => (url, Some(revision)),
+        _ => (spec.trim_end_matches('#'), None),
+    };
+
+    println!(
+        "Fetching template {} from {}...",
+        revision.unwrap_or("(default branch)"),
+        url
+    );
+    let repo = Repository::init(checkout_dir)?;
+    fetch_revision(&repo, url, revision)
+        .map_err(|e| format!("Failed to fetch template from {}: {}", url, e))?;
+    drop(repo);
+    fs::remove_dir_all(checkout_dir.join(".git"))?;
+
+    UserTemplate::load(checkout_dir)
+}
+
+// The per-user configuration directory: JUMAKE_CONFIG_DIR if set, otherwise
+// XDG_CONFIG_HOME/jumake or ~/.config/jumake, and %APPDATA%\jumake on Windows.
+pub fn default_config_dir() -> Option<PathBuf> {
+    if let Some(config_dir) = env::var_os(CONFIG_DIR_ENV_VAR) {
+        return Some(PathBuf::from(config_dir));
+    }
+
+    if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("jumake"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .map(|dir| dir.join("jumake"))
+    }
+}
+
+// Where user templates are looked up, in order: the --template-dir, then <config dir>/templates.
+pub fn template_search_dirs(template_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = template_dir.map(Path::to_path_buf).into_iter().collect();
+    if let Some(config_dir) = default_config_dir() {
+        dirs.push(config_dir.join("templates"));
+    }
+    dirs
+}
+
+// All user templates in `search_dirs`. A search directory may itself be a template, or hold
+// one template per sub-directory; when two templates share a name, the first one wins.
+// Templates that fail to load are skipped with a warning.
+pub fn find_user_templates(search_dirs: &[PathBuf]) -> Result<Vec<UserTemplate>, Box<dyn Error>> {
+    let mut templates: Vec<UserTemplate> = Vec::new();
+    for dir in search_dirs {
+        let mut candidates = Vec::new();
+        if dir.join(TEMPLATE_MANIFEST_FILE_NAME).is_file() {
+            candidates.push(dir.clone());
+        } else if dir.is_dir() {
+            let mut entries = fs::read_dir(dir)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            entries.sort();
+            candidates.extend(
+                entries
+                    .into_iter()
+                    .filter(|path| path.join(TEMPLATE_MANIFEST_FILE_NAME).is_file()),
+            );
+        }
+
+        for candidate in candidates {
+            // One broken template must not hide all the others.
+            let template = match UserTemplate::load(&candidate) {
+                Ok(template) => template,
+                Err(e) => {
+                    println!("Warning: Skipping template: {}", e);
+                    continue;
+                }
+            };
+            if !templates.iter().any(|t| t.name() == template.name()) {
+                templates.push(template);
+            }
+        }
+    }
+    Ok(templates)
+}
+
+// Where user `add` elements are looked up, in order: <project>/.jumake/templates/elements, then
+// <config dir>/templates/elements, apart from the project templates next to it.
+pub fn element_search_dirs(project_path: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![project_path.join(PROJECT_ELEMENTS_DIR)];
+    if let Some(config_dir) = default_config_dir() {
+        dirs.push(config_dir.join("templates").join("elements"));
+    }
+    dirs
+}
+
+// All user elements in `search_dirs`, one per sub-directory holding an element.toml; when two
+// elements share a name, the first one wins. Elements that fail to load are skipped with a
+// warning.
+pub fn find_user_elements(search_dirs: &[PathBuf]) -> Result<Vec<UserElement>, Box<dyn Error>> {
+    let mut elements: Vec<UserElement> = Vec::new();
+    for dir in search_dirs.iter().filter(|dir| dir.is_dir()) {
+        let mut entries = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort();
+
+        for entry in entries
+            .into_iter()
+            .filter(|path| path.join(ELEMENT_MANIFEST_FILE_NAME).is_file())
+        {
+            let element = match UserElement::load(&entry) {
+                Ok(element) => element,
+                Err(e) => {
+                    println!("Warning: Skipping element: {}", e);
+                    continue;
+                }
+            };
+            if !elements.iter().any(|e| e.name() == element.name()) {
+                elements.push(element);
+            }
+        }
+    }
+    Ok(elements)
+}
+
+pub fn find_user_element(
+    search_dirs: &[PathBuf],
+    name: &str,
+) -> Result<Option<UserElement>, Box<dyn Error>> {
+    Ok(find_user_elements(search_dirs)?
+        .into_iter()
+        .find(|element| element.name().eq_ignore_ascii_case(name)))
+}
+
+pub fn find_user_template(
+    search_dirs: &[PathBuf],
+    name: &str,
+) -> Result<Option<UserTemplate>, Box<dyn Error>> {
+    Ok(find_user_templates(search_dirs)?
+        .into_iter()
+        .find(|template| template.name().eq_ignore_ascii_case(name)))
+}
+
+fn is_relative_inside(path: &Path) -> bool {
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        && path.components().next().is_some()
+}
@@ -0,0 +1,102 @@
+// src/update_juce.rs
+
+use crate::context::Context;
+use crate::initialize_git::{fetch_revision, JUCE_SUBMODULE_PATH};
+use crate::manifest::{Manifest, MANIFEST_FILE_NAME};
+use git2::{Config, Oid, Repository, StatusOptions};
+use regex::Regex;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+// What `jumake juce update` changed.
+pub struct JuceUpdate {
+    pub revision: String,
+    pub old_commit: Oid,
+    pub new_commit: Oid,
+    pub old_version: Option<String>,
+    pub new_version: Option<String>,
+}
+
+// Move the project's JUCE submodule to `revision` (the manifest's revision, or JUCE's default
+// branch, if None) and stage the new gitlink, .gitmodules and jumake.toml for the next commit.
+pub fn update_juce(
+    context: &Context,
+    revision: Option<&str>,
+) -> Result<JuceUpdate, Box<dyn Error>> {
+    if let Some(juce_path) = &context.juce_path {
+        return Err(format!(
+            "This project uses the external JUCE checkout at {}; update it there.",
+            juce_path.display()
+        )
+        .into());
+    }
+
+    let juce_path = context.project_path.join(JUCE_SUBMODULE_PATH);
+    let juce_repo = Repository::open(&juce_path).map_err(|_| {
+        format!(
+            "No JUCE checkout found at {}. Run `git submodule update --init` first.",
+            juce_path.display()
+        )
+    })?;
+
+    let mut status_options = StatusOptions::new();
+    status_options.include_untracked(false);
+    if !juce_repo.statuses(Some(&mut status_options))?.is_empty() {
+        return Err(format!(
+            "{} has local changes; commit or discard them before updating JUCE.",
+            JUCE_SUBMODULE_PATH
+        )
+        .into());
+    }
+
+    let old_commit = juce_repo.head()?.peel_to_commit()?.id();
+    let old_version = read_juce_version(&juce_path);
+
+    let revision = revision.or(context.juce_revision.as_deref());
+    println!(
+        "Fetching JUCE {} from {}...",
+        revision.unwrap_or("(default branch)"),
+        context.juce_source
+    );
+    let fetched = fetch_revision(&juce_repo, &context.juce_source, revision)?;
+
+    // Stage the new gitlink and keep .gitmodules' branch in sync with the checked-out revision.
+    let mut repo = Repository::open(&context.project_path)?;
+    repo.find_submodule(JUCE_SUBMODULE_PATH)?
+        .add_to_index(true)?;
+    let branch_key = format!("submodule.{}.branch", JUCE_SUBMODULE_PATH);
+    match &fetched.branch {
+        Some(branch) => repo.submodule_set_branch(JUCE_SUBMODULE_PATH, branch)?,
+        None => {
+            let mut gitmodules = Config::open(&context.project_path.join(".gitmodules"))?;
+            // Removing a key that was never set is not an error worth reporting.
+            let _ = gitmodules.remove(&branch_key);
+        }
+    }
+
+    let mut manifest = Manifest::load(&context.project_path)?;
+    manifest.juce.revision = Some(fetched.revision.clone());
+    manifest.save(&context.project_path)?;
+
+    let mut index = repo.index()?;
+    index.add_path(Path::new(".gitmodules"))?;
+    index.add_path(Path::new(MANIFEST_FILE_NAME))?;
+    index.write()?;
+
+    Ok(JuceUpdate {
+        revision: fetched.revision,
+        old_commit,
+        new_commit: fetched.commit,
+        old_version,
+        new_version: read_juce_version(&juce_path),
+    })
+}
+
+// The JUCE version declared by `project(JUCE VERSION x.y.z ...)` in JUCE's CMakeLists.txt.
+pub fn read_juce_version(juce_path: &Path) -> Option<String> {
+    let content = fs::read_to_string(juce_path.join("CMakeLists.txt")).ok()?;
+    let re = Regex::new(r"project\(\s*JUCE\s+VERSION\s+([0-9][0-9A-Za-z.\-]*)").unwrap();
+    re.captures(&content)
+        .map(|captures| captures[1].to_string())
+}
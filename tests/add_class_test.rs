@@ -0,0 +1,493 @@
+// tests/add_class_test.rs
+
+mod common;
+
+use common::create_console_project;
+use jumake::{
+    context::Context,
+    create_files::{
+        add_class, add_link_library, create_source_files, link_target, links_module, ClassOptions,
+    },
+};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn test_add_link_library() {
+    let content = "target_link_libraries(${PROJECT_NAME}\n    PRIVATE\n        # Data        # A comment\n        juce::juce_core\n    PUBLIC\n        juce::juce_recommended_config_flags)\n";
+    assert_eq!(
+        add_link_library(content, "${PROJECT_NAME}", "juce::juce_dsp").unwrap(),
+        "target_link_libraries(${PROJECT_NAME}\n    PRIVATE\n        # Data        # A comment\n        juce::juce_core\n        juce::juce_dsp\n    PUBLIC\n        juce::juce_recommended_config_flags)\n"
+    );
+
+    // The closing parenthesis moves to the new last library.
+    let content = "target_link_libraries(${PROJECT_NAME}\n    PRIVATE\n        juce::juce_core)\n";
+    assert_eq!(
+        add_link_library(content, "${PROJECT_NAME}", "juce::juce_dsp").unwrap(),
+        "target_link_libraries(${PROJECT_NAME}\n    PRIVATE\n        juce::juce_core\n        juce::juce_dsp)\n"
+    );
+
+    // Libraries that are already linked are left alone.
+    let linked = add_link_library(content, "${PROJECT_NAME}", "juce::juce_core").unwrap();
+    assert_eq!(linked, content);
+
+    assert!(add_link_library(
+        "target_link_libraries(App PRIVATE juce::juce_core)\n",
+        "App",
+        "juce::juce_dsp"
+    )
+    .is_none());
+
+    // Other targets' calls are left alone.
+    assert!(add_link_library(content, "App", "juce::juce_dsp").is_none());
+    let content = "target_link_libraries(AppTests\n    PRIVATE\n        juce::juce_core)\n";
+    assert!(add_link_library(content, "App", "juce::juce_dsp").is_none());
+}
+
+#[test]
+fn test_add_processor() {
+    let project_path = PathBuf::from("/tmp/add_processor_test_project");
+    let context = create_console_project(&project_path);
+
+    add_class(&context, "processor", "Gain", &ClassOptions::default())
+        .expect("Failed to add processor");
+    add_class(
+        &context,
+        "processor",
+        "FilterProcessor",
+        &ClassOptions::default(),
+    )
+    .expect("Failed to add processor");
+
+    let src_path = project_path.join("src");
+    let header = fs::read_to_string(src_path.join("GainProcessor.h")).unwrap();
+    for expected in [
+        "#include <juce_dsp/juce_dsp.h>",
+        "class GainProcessor\n",
+        "    void prepare(const juce::dsp::ProcessSpec& spec);\n",
+        "    void process(const juce::dsp::ProcessContextReplacing<float>& context);\n",
+        "    void reset();\n",
+    ] {
+        assert!(
+            header.contains(expected),
+            "Missing '{}' in:\n{}",
+            expected,
+            header
+        );
+    }
+    assert!(!header.contains("juce_gui_extra"));
+    assert!(src_path.join("GainProcessor.cpp").exists());
+    assert!(src_path.join("FilterProcessor.h").exists());
+
+    let cmakelists = fs::read_to_string(src_path.join("CMakeLists.txt")).unwrap();
+    assert!(
+        cmakelists.contains(
+            "GainProcessor.cpp\n        FilterProcessor.cpp\n        # JUMAKE_SOURCES_END"
+        ),
+        "{}",
+        cmakelists
+    );
+    assert!(
+        cmakelists.contains("        juce::juce_core\n        juce::juce_dsp\n    PUBLIC"),
+        "{}",
+        cmakelists
+    );
+    assert_eq!(cmakelists.matches("juce::juce_dsp").count(), 1);
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
+
+#[test]
+fn test_links_module() {
+    let content =
+        "target_link_libraries(${PROJECT_NAME}\n    PRIVATE\n        juce::juce_gui_extra)\n";
+    assert!(links_module(content, "juce::juce_gui_extra"));
+    assert!(links_module(content, "juce::juce_gui_basics"));
+    assert!(links_module(content, "juce::juce_core"));
+    assert!(!links_module(content, "juce::juce_audio_processors"));
+    assert!(!links_module(content, "juce::juce_gui"));
+}
+
+#[test]
+fn test_link_target() {
+    assert_eq!(
+        link_target("juce_add_gui_app(App\n    PRODUCT_NAME \"App\")\n").as_deref(),
+        Some("App")
+    );
+    assert_eq!(
+        link_target("juce_add_module(${CMAKE_CURRENT_SOURCE_DIR}/gain)\njuce_add_console_app(${PROJECT_NAME}_demo)\n")
+            .as_deref(),
+        Some("${PROJECT_NAME}_demo")
+    );
+    assert_eq!(
+        link_target("add_library(Helpers STATIC)\njuce_add_plugin( Synth )\n").as_deref(),
+        Some("Synth")
+    );
+    assert_eq!(
+        link_target("add_library(${PROJECT_NAME} STATIC)\n").as_deref(),
+        Some("${PROJECT_NAME}")
+    );
+    assert_eq!(link_target("# juce_add_gui_app(App)\n"), None);
+}
+
+#[test]
+fn test_modules_are_linked_to_the_project_target() {
+    let project_path = PathBuf::from("/tmp/link_target_test_project");
+    let _ = fs::remove_dir_all(&project_path); // Clean up before running the test
+    let src_path = project_path.join("src");
+    fs::create_dir_all(&src_path).expect("Failed to create test project directory");
+    // Projects adopted with `jumake init` keep their own target names.
+    fs::write(
+        src_path.join("CMakeLists.txt"),
+        "juce_add_gui_app(App)\n\ntarget_sources(App\n    PRIVATE\n        # JUMAKE_SOURCES_BEGIN\n        Main.cpp\n        # JUMAKE_SOURCES_END\n)\n",
+    )
+    .unwrap();
+    let context = Context {
+        project_name: String::from("Tool"),
+        project_path: project_path.clone(),
+        template_name: Some(String::from("GuiApplication")),
+        ..Default::default()
+    };
+    let cmakelists = || fs::read_to_string(src_path.join("CMakeLists.txt")).unwrap();
+
+    add_class(&context, "look-and-feel", "Dark", &ClassOptions::default())
+        .expect("Failed to add look and feel");
+    add_class(&context, "processor", "Filter", &ClassOptions::default())
+        .expect("Failed to add processor");
+    let content = cmakelists();
+    assert!(
+        content.ends_with(
+            ")\n\ntarget_link_libraries(App\n    PRIVATE\n        juce::juce_gui_basics\n        juce::juce_dsp)\n"
+        ),
+        "{}",
+        content
+    );
+    assert!(!content.contains("${PROJECT_NAME}"), "{}", content);
+
+    // Without a juce_add_* call there is no target to link to.
+    fs::write(
+        src_path.join("CMakeLists.txt"),
+        content.replace("juce_add_gui_app(App)\n", ""),
+    )
+    .unwrap();
+    let error = add_class(&context, "audio-app", "Player", &ClassOptions::default())
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(error.contains("no juce_add_* call"), "{}", error);
+    assert!(!src_path.join("PlayerComponent.h").exists());
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+
+    // The JuceModule template links the module into its demo app.
+    let context = Context {
+        project_name: String::from("gain"),
+        template_name: Some(String::from("JuceModule")),
+        ..context
+    };
+    create_source_files(&context).expect("Failed to create source files");
+    add_class(&context, "processor", "Smoother", &ClassOptions::default())
+        .expect("Failed to add processor");
+    let content = cmakelists();
+    assert!(
+        content.contains("target_link_libraries(${PROJECT_NAME}_demo\n    PRIVATE\n        gain\n        juce::juce_core\n        juce::juce_dsp\n    PUBLIC"),
+        "{}",
+        content
+    );
+    assert_eq!(content.matches("target_link_libraries").count(), 1);
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
+
+#[test]
+fn test_element_suffixes() {
+    let project_path = PathBuf::from("/tmp/element_suffix_test_project");
+    let context = create_console_project(&project_path);
+    let src_path = project_path.join("src");
+
+    // Components always get the suffix; the newer element types don't repeat it.
+    for (element_type, name, file) in [
+        ("component", "MainComponent", "MainComponentComponent.h"),
+        ("component", "Knob", "KnobComponent.h"),
+        ("timer-component", "MeterComponent", "MeterComponent.h"),
+        ("processor", "FilterProcessor", "FilterProcessor.h"),
+        ("thread", "Loader", "LoaderThread.h"),
+    ] {
+        add_class(&context, element_type, name, &ClassOptions::default())
+            .unwrap_or_else(|e| panic!("Failed to add {} {}: {}", element_type, name, e));
+        assert!(src_path.join(file).exists(), "Missing {}", file);
+    }
+    assert!(!src_path.join("MainComponent.h").exists());
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
+
+#[test]
+fn test_add_element_types() {
+    let project_path = PathBuf::from("/tmp/add_element_test_project");
+    let context = create_console_project(&project_path);
+    let src_path = project_path.join("src");
+    let cmakelists = || fs::read_to_string(src_path.join("CMakeLists.txt")).unwrap();
+    let original = cmakelists();
+
+    // juce_core is already linked by the console app.
+    add_class(&context, "thread", "Loader", &ClassOptions::default())
+        .expect("Failed to add thread");
+    assert_eq!(
+        cmakelists(),
+        original.replace(
+            "# JUMAKE_SOURCES_END",
+            "LoaderThread.cpp\n        # JUMAKE_SOURCES_END"
+        )
+    );
+
+    add_class(&context, "look-and-feel", "Dark", &ClassOptions::default())
+        .expect("Failed to add look and feel");
+    add_class(
+        &context,
+        "timer-component",
+        "Meter",
+        &ClassOptions::default(),
+    )
+    .expect("Failed to add timer component");
+    add_class(
+        &context,
+        "audio-processor",
+        "Mixer",
+        &ClassOptions::default(),
+    )
+    .expect("Failed to add audio processor");
+    add_class(&context, "audio-app", "Main", &ClassOptions::default())
+        .expect("Failed to add audio app component");
+
+    for (file, expected) in [
+        ("LoaderThread.h", "class LoaderThread: public juce::Thread"),
+        ("LoaderThread.cpp", "    : juce::Thread(\"LoaderThread\")"),
+        (
+            "DarkLookAndFeel.h",
+            "class DarkLookAndFeel: public juce::LookAndFeel_V4",
+        ),
+        (
+            "MeterComponent.h",
+            "class MeterComponent: public juce::Component, private juce::Timer",
+        ),
+        ("MeterComponent.cpp", "void MeterComponent::timerCallback()"),
+        (
+            "MixerProcessor.h",
+            "class MixerProcessor: public juce::AudioProcessor",
+        ),
+        ("MixerProcessor.cpp", "return \"MixerProcessor\";"),
+        (
+            "MainComponent.h",
+            "class MainComponent: public juce::AudioAppComponent",
+        ),
+        ("MainComponent.cpp", "    shutdownAudio();"),
+    ] {
+        let content = fs::read_to_string(src_path.join(file)).unwrap();
+        assert!(
+            content.contains(expected),
+            "Missing '{}' in:\n{}",
+            expected,
+            content
+        );
+    }
+
+    // The timer component reuses juce_gui_basics linked for the look and feel.
+    let cmakelists = cmakelists();
+    assert!(
+        cmakelists.contains(
+            "        juce::juce_core\n        juce::juce_gui_basics\n        juce::juce_audio_processors\n        juce::juce_audio_utils\n    PUBLIC"
+        ),
+        "{}",
+        cmakelists
+    );
+    assert_eq!(cmakelists.matches("juce::juce_gui_basics").count(), 1);
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
+
+#[test]
+fn test_add_class_in_sub_directory_and_namespace() {
+    let project_path = PathBuf::from("/tmp/nested_class_test_project");
+    let context = create_console_project(&project_path);
+
+    let options = ClassOptions {
+        namespace: Some(String::from("mylib::dsp")),
+        ..Default::default()
+    };
+    add_class(&context, "class", "dsp/filters/Biquad", &options).expect("Failed to add class");
+    add_class(&context, "processor", "dsp/Gain", &ClassOptions::default())
+        .expect("Failed to add processor");
+
+    let src_path = project_path.join("src");
+    let header = fs::read_to_string(src_path.join("dsp/filters/Biquad.h")).unwrap();
+    assert!(
+        header.starts_with("#pragma once\n\n#include <juce_gui_extra/juce_gui_extra.h>\n\nnamespace mylib\n{\nnamespace dsp\n{\n\nclass Biquad\n"),
+        "{}",
+        header
+    );
+    assert!(
+        header.ends_with("};\n\n} // namespace dsp\n} // namespace mylib\n"),
+        "{}",
+        header
+    );
+    let source = fs::read_to_string(src_path.join("dsp/filters/Biquad.cpp")).unwrap();
+    assert!(
+        source.starts_with(
+            "#include \"Biquad.h\"\n\nnamespace mylib\n{\nnamespace dsp\n{\n\nBiquad::Biquad()\n"
+        ),
+        "{}",
+        source
+    );
+    assert!(src_path.join("dsp/GainProcessor.h").exists());
+
+    let cmakelists = fs::read_to_string(src_path.join("CMakeLists.txt")).unwrap();
+    assert!(
+        cmakelists.contains(
+            "        dsp/filters/Biquad.cpp\n        dsp/GainProcessor.cpp\n        # JUMAKE_SOURCES_END"
+        ),
+        "{}",
+        cmakelists
+    );
+
+    // The same class name may live in another directory, but not twice in the same one.
+    add_class(&context, "class", "Biquad", &ClassOptions::default()).expect("Failed to add class");
+    let cmakelists = fs::read_to_string(src_path.join("CMakeLists.txt")).unwrap();
+    assert!(
+        cmakelists.contains("        Biquad.cpp\n        # JUMAKE_SOURCES_END"),
+        "{}",
+        cmakelists
+    );
+    assert!(add_class(&context, "class", "dsp/filters/Biquad", &options).is_err());
+
+    for (name, namespace) in [
+        ("../outside/Biquad", None),
+        ("/abs/Biquad", None),
+        ("dsp//Biquad", None),
+        ("dsp/2Biquad", None),
+        ("Biquad2", Some("mylib::")),
+        ("Biquad2", Some("my lib")),
+    ] {
+        let options = ClassOptions {
+            namespace: namespace.map(String::from),
+            ..Default::default()
+        };
+        assert!(
+            add_class(&context, "class", name, &options).is_err(),
+            "{} {:?}",
+            name,
+            namespace
+        );
+    }
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
+
+#[test]
+fn test_header_only_and_source_only_classes() {
+    let project_path = PathBuf::from("/tmp/header_only_test_project");
+    let context = create_console_project(&project_path);
+    let src_path = project_path.join("src");
+    let cmakelists = || fs::read_to_string(src_path.join("CMakeLists.txt")).unwrap();
+    let original = cmakelists();
+
+    let header_only = ClassOptions {
+        header_only: true,
+        ..Default::default()
+    };
+    add_class(&context, "class", "Ramp", &header_only).expect("Failed to add header-only class");
+    assert!(!src_path.join("Ramp.cpp").exists());
+    let header = fs::read_to_string(src_path.join("Ramp.h")).unwrap();
+    for expected in [
+        "#pragma once\n\n#include <juce_gui_extra/juce_gui_extra.h>\n\nclass Ramp\n",
+        "};\n\ninline Ramp::Ramp()\n{\n}\n\ninline Ramp::~Ramp()\n",
+    ] {
+        assert!(
+            header.contains(expected),
+            "Missing '{}' in:\n{}",
+            expected,
+            header
+        );
+    }
+    assert!(!header.contains("#include \"Ramp.h\""), "{}", header);
+    assert_eq!(cmakelists(), original);
+
+    // Header-only processors still link juce_dsp, and listed headers go into the source block.
+    let listed = ClassOptions {
+        list_headers: true,
+        ..header_only.clone()
+    };
+    add_class(&context, "processor", "dsp/Gain", &listed).expect("Failed to add processor");
+    let header = fs::read_to_string(src_path.join("dsp/GainProcessor.h")).unwrap();
+    assert!(
+        header.contains("inline void GainProcessor::reset()\n"),
+        "{}",
+        header
+    );
+    let with_header = cmakelists();
+    for expected in [
+        "        dsp/GainProcessor.h\n        # JUMAKE_SOURCES_END",
+        "        juce::juce_dsp\n",
+    ] {
+        assert!(
+            with_header.contains(expected),
+            "Missing '{}' in:\n{}",
+            expected,
+            with_header
+        );
+    }
+
+    let list_headers = ClassOptions {
+        list_headers: true,
+        ..Default::default()
+    };
+    add_class(&context, "thread", "Loader", &list_headers).expect("Failed to add thread");
+    assert!(
+        cmakelists().contains(
+            "        LoaderThread.h\n        LoaderThread.cpp\n        # JUMAKE_SOURCES_END"
+        ),
+        "{}",
+        cmakelists()
+    );
+
+    let source_only = ClassOptions {
+        source_only: true,
+        namespace: Some(String::from("ui")),
+        ..Default::default()
+    };
+    add_class(&context, "component", "Panel", &source_only).expect("Failed to add component");
+    assert!(!src_path.join("PanelComponent.h").exists());
+    let source = fs::read_to_string(src_path.join("PanelComponent.cpp")).unwrap();
+    assert!(
+        source.starts_with("#include <juce_gui_extra/juce_gui_extra.h>\n\nnamespace ui\n{\n\nclass PanelComponent: public juce::Component\n"),
+        "{}",
+        source
+    );
+    assert!(
+        source.contains("};\n\nPanelComponent::PanelComponent()\n"),
+        "{}",
+        source
+    );
+    assert!(!source.contains("#pragma once"), "{}", source);
+    assert!(
+        cmakelists().contains("        PanelComponent.cpp\n        # JUMAKE_SOURCES_END"),
+        "{}",
+        cmakelists()
+    );
+
+    for options in [
+        ClassOptions {
+            source_only: true,
+            ..header_only.clone()
+        },
+        ClassOptions {
+            list_headers: true,
+            source_only: true,
+            ..Default::default()
+        },
+    ] {
+        assert!(add_class(&context, "class", "Invalid", &options).is_err());
+    }
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
@@ -0,0 +1,104 @@
+// tests/builtin_templates_test.rs
+
+use jumake::{
+    context::Context,
+    create_files::{generate_source_files, BUILTIN_TEMPLATES},
+};
+use std::path::Path;
+
+#[test]
+fn test_builtin_templates_render() {
+    for template in &BUILTIN_TEMPLATES {
+        let context = Context {
+            project_name: String::from("Shared Dsp"),
+            template_name: Some(template.name.to_string()),
+            ..Default::default()
+        };
+        let files = generate_source_files(&context)
+            .unwrap_or_else(|e| panic!("Failed to render {}: {}", template.name, e));
+
+        for file in &files {
+            let content = String::from_utf8_lossy(&file.content);
+            assert!(
+                !content.contains("{{") && !file.path.to_string_lossy().contains("{{"),
+                "Unrendered placeholder in {} of {}",
+                file.path.display(),
+                template.name
+            );
+        }
+        let cmakelists = files
+            .iter()
+            .find(|file| file.path == Path::new("src/CMakeLists.txt"))
+            .unwrap_or_else(|| panic!("{} has no src/CMakeLists.txt", template.name));
+        let cmakelists = String::from_utf8_lossy(&cmakelists.content);
+        assert!(
+            cmakelists.contains(&format!("set(JUMAKE_TEMPLATE \"{}\")", template.name)),
+            "{}",
+            cmakelists
+        );
+        assert!(
+            cmakelists.contains("# JUMAKE_SOURCES_BEGIN"),
+            "{}",
+            cmakelists
+        );
+    }
+}
+
+#[test]
+fn test_juce_module_is_named_after_the_namespace() {
+    let context = Context {
+        project_name: String::from("Shared Dsp"),
+        template_name: Some(String::from("JuceModule")),
+        ..Default::default()
+    };
+    let files = generate_source_files(&context).expect("Failed to render JuceModule");
+    let header = files
+        .iter()
+        .find(|file| file.path == Path::new("src/shared_dsp/shared_dsp.h"))
+        .expect("Missing module header");
+    let header = String::from_utf8_lossy(&header.content);
+    assert!(header.contains("BEGIN_JUCE_MODULE_DECLARATION"));
+    assert!(
+        header.contains("  ID:                 shared_dsp\n"),
+        "{}",
+        header
+    );
+    assert!(files
+        .iter()
+        .any(|file| file.path == Path::new("src/shared_dsp/shared_dsp.cpp")));
+}
+
+#[test]
+fn test_juce_module_targets_do_not_clash_with_lowercase_names() {
+    let context = Context {
+        project_name: String::from("mylib"),
+        template_name: Some(String::from("JuceModule")),
+        ..Default::default()
+    };
+    let files = generate_source_files(&context).expect("Failed to render JuceModule");
+    assert!(files
+        .iter()
+        .any(|file| file.path == Path::new("src/mylib/mylib.h")));
+    let cmakelists = files
+        .iter()
+        .find(|file| file.path == Path::new("src/CMakeLists.txt"))
+        .expect("Missing src/CMakeLists.txt");
+    let cmakelists =
+        String::from_utf8_lossy(&cmakelists.content).replace("${PROJECT_NAME}", "mylib");
+
+    // juce_add_module names its target after the module directory.
+    let targets: Vec<&str> = cmakelists
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if let Some(module) = line.strip_prefix("juce_add_module(") {
+                module.trim_end_matches(')').rsplit('/').next()
+            } else {
+                line.strip_prefix("juce_add_console_app(")
+                    .map(|rest| rest.split_whitespace().next().unwrap_or(rest))
+            }
+        })
+        .collect();
+    assert_eq!(targets, ["mylib", "mylib_demo"], "{}", cmakelists);
+    assert!(cmakelists.contains("PRODUCT_NAME mylib)"), "{}", cmakelists);
+}
@@ -0,0 +1,144 @@
+// tests/cache_test.rs
+
+mod common;
+
+use common::{add_juce_commit, create_fake_juce_repo};
+use git2::Repository;
+use jumake::{
+    cache::{cache_key, list_cached_revisions, prune_cache, update_cache},
+    context::Context,
+    create_project::create_project,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn juce_head(project_path: &Path) -> git2::Oid {
+    let repo = Repository::open(project_path.join("modules").join("JUCE")).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+    head
+}
+
+#[test]
+fn test_projects_are_created_from_the_juce_cache() {
+    let root = PathBuf::from("/tmp/juce_cache_project_test");
+    let _ = fs::remove_dir_all(&root);
+    let juce_repo_path = root.join("JUCE.git");
+    let juce = create_fake_juce_repo(&juce_repo_path);
+    let cache_dir = root.join("cache");
+
+    let new_context = |name: &str| Context {
+        project_name: String::from(name),
+        project_path: root.join(name),
+        template_name: Some(String::from("ConsoleApp")),
+        juce_source: juce.url.clone(),
+        juce_revision: Some(String::from("8.0.0")),
+        juce_cache_dir: Some(cache_dir.clone()),
+        ..Default::default()
+    };
+
+    let first = new_context("FirstProject");
+    create_project(&first).expect("Failed to create first project");
+    assert_eq!(juce_head(&first.project_path), juce.second_commit);
+
+    let cached = list_cached_revisions(&cache_dir).unwrap();
+    assert_eq!(cached.len(), 1);
+    assert_eq!(cached[0].url, juce.url);
+    assert_eq!(cached[0].fetched.revision, "8.0.0");
+    assert_eq!(cached[0].fetched.commit, juce.second_commit);
+
+    // The second project must not need the upstream repository at all.
+    fs::rename(&juce_repo_path, root.join("JUCE.offline")).unwrap();
+    let second = new_context("SecondProject");
+    create_project(&second).expect("Failed to create project from the cache");
+    assert_eq!(juce_head(&second.project_path), juce.second_commit);
+    let gitmodules = fs::read_to_string(second.project_path.join(".gitmodules")).unwrap();
+    assert!(gitmodules.contains(&format!("url = {}", juce.url)));
+
+    // Projects keep working after the cache is pruned.
+    let removed = prune_cache(&cache_dir, 0).unwrap();
+    assert_eq!(removed.len(), 1);
+    assert!(list_cached_revisions(&cache_dir).unwrap().is_empty());
+    let repo = Repository::open(second.project_path.join("modules").join("JUCE")).unwrap();
+    let tree = repo.head().unwrap().peel_to_tree().unwrap();
+    assert!(tree.get_name("CMakeLists.txt").is_some());
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_cache_update_moves_branches_only() {
+    let root = PathBuf::from("/tmp/juce_cache_update_test");
+    let _ = fs::remove_dir_all(&root);
+    let juce_repo_path = root.join("JUCE.git");
+    let juce = create_fake_juce_repo(&juce_repo_path);
+    let cache_dir = root.join("cache");
+
+    for (name, revision) in [("BranchProject", "develop"), ("TagProject", "7.0.0")] {
+        let context = Context {
+            project_name: String::from(name),
+            project_path: root.join(name),
+            template_name: Some(String::from("ConsoleApp")),
+            juce_source: juce.url.clone(),
+            juce_revision: Some(String::from(revision)),
+            juce_cache_dir: Some(cache_dir.clone()),
+            ..Default::default()
+        };
+        create_project(&context).expect("Failed to create project");
+    }
+
+    let new_develop = add_juce_commit(&juce_repo_path, "develop", "8.2.0");
+    let updated = update_cache(&cache_dir).expect("Failed to update cache");
+    assert_eq!(updated.len(), 1);
+    let (old, new) = &updated[0];
+    assert_eq!(old.fetched.commit, juce.develop_commit);
+    assert_eq!(new.fetched.commit, new_develop);
+    assert_eq!(new.fetched.branch.as_deref(), Some("develop"));
+
+    // Recently used revisions survive a prune with the default age.
+    assert!(prune_cache(&cache_dir, 30).unwrap().is_empty());
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_new_projects_get_the_latest_commit_of_cached_branches() {
+    let root = PathBuf::from("/tmp/juce_cache_refetch_test");
+    let _ = fs::remove_dir_all(&root);
+    let juce_repo_path = root.join("JUCE.git");
+    let juce = create_fake_juce_repo(&juce_repo_path);
+    let cache_dir = root.join("cache");
+
+    let new_context = |name: &str, revision: Option<&str>| Context {
+        project_name: String::from(name),
+        project_path: root.join(name),
+        template_name: Some(String::from("ConsoleApp")),
+        juce_source: juce.url.clone(),
+        juce_revision: revision.map(String::from),
+        juce_cache_dir: Some(cache_dir.clone()),
+        ..Default::default()
+    };
+    for (name, revision) in [("Default", None), ("Develop", Some("develop"))] {
+        create_project(&new_context(name, revision)).expect("Failed to create project");
+    }
+    assert_eq!(juce_head(&root.join("Default")), juce.second_commit);
+    assert_eq!(juce_head(&root.join("Develop")), juce.develop_commit);
+
+    let new_main = add_juce_commit(&juce_repo_path, "main", "8.0.1");
+    let new_develop = add_juce_commit(&juce_repo_path, "develop", "8.2.0");
+    for (name, revision) in [("NewDefault", None), ("NewDevelop", Some("develop"))] {
+        create_project(&new_context(name, revision)).expect("Failed to create project");
+    }
+    assert_eq!(juce_head(&root.join("NewDefault")), new_main);
+    assert_eq!(juce_head(&root.join("NewDevelop")), new_develop);
+    assert_eq!(list_cached_revisions(&cache_dir).unwrap().len(), 2);
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_cache_keys_do_not_collide() {
+    assert_eq!(cache_key("8.0.3"), "8.0.3");
+    assert_ne!(cache_key("feature/x"), cache_key("feature_x"));
+    assert_ne!(cache_key("a_2fb"), cache_key("a/b"));
+    assert_ne!(cache_key(".."), "..");
+}
@@ -0,0 +1,279 @@
+// tests/cli_test.rs
+
+mod common;
+
+use common::create_fake_juce_repo;
+use jumake::manifest::Manifest;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+fn jumake() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_jumake"));
+    command.stdin(Stdio::null());
+    command
+}
+
+#[test]
+fn test_new_without_template_fails_when_not_interactive() {
+    let root = PathBuf::from("/tmp/cli_no_input_test");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let output = jumake()
+        .args(["new", "NoTemplate"])
+        .current_dir(&root)
+        .output()
+        .expect("Failed to run jumake");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No template given"), "{}", stderr);
+    assert!(!root.join("NoTemplate").exists());
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_new_with_yes_uses_default_template() {
+    let root = PathBuf::from("/tmp/cli_yes_test");
+    let _ = fs::remove_dir_all(&root);
+    let juce = create_fake_juce_repo(&root.join("JUCE.git"));
+
+    let status = jumake()
+        .args(["new", "Defaults", "--yes", "--no-input", "--no-cache"])
+        .env("JUMAKE_JUCE_URL", &juce.url)
+        .current_dir(&root)
+        .status()
+        .expect("Failed to run jumake");
+    assert!(status.success());
+
+    let manifest = Manifest::load(&root.join("Defaults")).unwrap();
+    assert_eq!(manifest.project.template, "GuiApplication");
+    assert_eq!(manifest.juce.source, juce.url);
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_new_with_user_template() {
+    let root = PathBuf::from("/tmp/cli_user_template_test");
+    let _ = fs::remove_dir_all(&root);
+    let juce = create_fake_juce_repo(&root.join("JUCE.git"));
+    let template_dir = root.join("templates").join("Minimal");
+    fs::create_dir_all(template_dir.join("src")).unwrap();
+    fs::write(
+        template_dir.join("template.toml"),
+        "[template]\nname = \"Minimal\"\n\n[[files]]\nsource = \"src/CMakeLists.txt\"\n\n\
+         [placeholders.target_kind]\n",
+    )
+    .unwrap();
+    fs::write(
+        template_dir.join("src").join("CMakeLists.txt"),
+        "juce_add_{{target_kind}}(${PROJECT_NAME})\n",
+    )
+    .unwrap();
+
+    let run = |extra: &[&str]| {
+        jumake()
+            .args(["new", "Custom", "--template", "Minimal", "--no-cache"])
+            .args(["--template-dir", root.join("templates").to_str().unwrap()])
+            .args(extra)
+            .env("JUMAKE_JUCE_URL", &juce.url)
+            .env("JUMAKE_CONFIG_DIR", root.join("config"))
+            .current_dir(&root)
+            .output()
+            .expect("Failed to run jumake")
+    };
+
+    // The placeholder has no default, so it must be given without a terminal.
+    let output = run(&[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--var target_kind="), "{}", stderr);
+
+    let output = run(&["--var", "target_kind=console_app"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let manifest = Manifest::load(&root.join("Custom")).unwrap();
+    assert_eq!(manifest.project.template, "Minimal");
+    let cmake = fs::read_to_string(root.join("Custom").join("src").join("CMakeLists.txt")).unwrap();
+    assert!(
+        cmake.contains("juce_add_console_app(${PROJECT_NAME})"),
+        "{}",
+        cmake
+    );
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_template_list_and_export() {
+    let root = PathBuf::from("/tmp/cli_template_command_test");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    let run = |args: &[&str]| {
+        let output = jumake()
+            .args(args)
+            .env("JUMAKE_CONFIG_DIR", root.join("config"))
+            .current_dir(&root)
+            .output()
+            .expect("Failed to run jumake");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    let list = run(&["template", "list"]);
+    assert!(list.contains("ConsoleApp"), "{}", list);
+    assert!(!list.contains("User templates"), "{}", list);
+
+    run(&["template", "export", "console-app", "config/templates/Tool"]);
+    let list = run(&["template", "list"]);
+    assert!(list.contains("User templates:"), "{}", list);
+    assert!(list.contains("Tool"), "{}", list);
+
+    let show = run(&["template", "show", "Tool"]);
+    assert!(show.contains("src/Main.cpp"), "{}", show);
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_parameter_flags_are_rejected_for_classes() {
+    let root = PathBuf::from("/tmp/cli_parameter_flags_test");
+    let _ = fs::remove_dir_all(&root);
+    let juce = create_fake_juce_repo(&root.join("JUCE.git"));
+
+    let status = jumake()
+        .args([
+            "new",
+            "Tool",
+            "--template",
+            "console-app",
+            "--yes",
+            "--no-input",
+        ])
+        .args(["--no-cache"])
+        .env("JUMAKE_JUCE_URL", &juce.url)
+        .current_dir(&root)
+        .status()
+        .expect("Failed to run jumake");
+    assert!(status.success());
+
+    let output = jumake()
+        .args([
+            "add",
+            "class",
+            "Filter",
+            "--range",
+            "0:1",
+            "--choices",
+            "a,b",
+        ])
+        .current_dir(root.join("Tool"))
+        .output()
+        .expect("Failed to run jumake");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--range, --choices only apply to parameters"),
+        "{}",
+        stderr
+    );
+    assert!(!root.join("Tool/src/Filter.h").exists());
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_company_names_that_break_cmake_are_rejected() {
+    let root = PathBuf::from("/tmp/cli_company_name_test");
+    let _ = fs::remove_dir_all(&root);
+    let juce = create_fake_juce_repo(&root.join("JUCE.git"));
+
+    let output = jumake()
+        .args([
+            "new",
+            "Foo",
+            "--template",
+            "audio-plugin",
+            "--yes",
+            "--no-input",
+        ])
+        .args(["--no-cache", "--company", "Foo \"Bar\" Audio"])
+        .env("JUMAKE_JUCE_URL", &juce.url)
+        .current_dir(&root)
+        .output()
+        .expect("Failed to run jumake");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("must not contain quotes"), "{}", stderr);
+    assert!(!root.join("Foo").exists());
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_add_user_element_from_config_directory() {
+    let root = PathBuf::from("/tmp/cli_config_element_test");
+    let _ = fs::remove_dir_all(&root);
+    let juce = create_fake_juce_repo(&root.join("JUCE.git"));
+    let config = root.join("config");
+    let element = config.join("templates").join("elements").join("gadget");
+    fs::create_dir_all(&element).unwrap();
+    fs::write(
+        element.join("element.toml"),
+        "[element]\nname = \"gadget\"\nsuffix = \"Gadget\"\n\n[files]\nheader = \"Gadget.h\"\nsource = \"Gadget.cpp\"\n",
+    )
+    .unwrap();
+    fs::write(element.join("Gadget.h"), "class {{class_name}};\n").unwrap();
+    fs::write(element.join("Gadget.cpp"), "#include \"{{header_path}}\"\n").unwrap();
+
+    let status = jumake()
+        .args([
+            "new",
+            "Tool",
+            "--template",
+            "console-app",
+            "--yes",
+            "--no-input",
+        ])
+        .args(["--no-cache"])
+        .env("JUMAKE_JUCE_URL", &juce.url)
+        .env("JUMAKE_CONFIG_DIR", &config)
+        .current_dir(&root)
+        .status()
+        .expect("Failed to run jumake");
+    assert!(status.success());
+
+    let output = jumake()
+        .args(["add", "gadget", "Dial"])
+        .env("JUMAKE_CONFIG_DIR", &config)
+        .current_dir(root.join("Tool"))
+        .output()
+        .expect("Failed to run jumake");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(root.join("Tool/src/DialGadget.h").exists());
+
+    // The elements directory sits next to the user templates without being mistaken for one.
+    let output = jumake()
+        .args(["template", "list"])
+        .env("JUMAKE_CONFIG_DIR", &config)
+        .output()
+        .expect("Failed to run jumake");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Warning"), "{}", stdout);
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
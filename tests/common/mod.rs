@@ -0,0 +1,88 @@
+// tests/common/mod.rs
+
+use git2::{Oid, Repository, Signature};
+use jumake::context::Context;
+use jumake::create_files::create_source_files;
+use std::fs;
+use std::path::Path;
+
+// Commits of a fake JUCE repository used to test cloning without network access.
+#[allow(dead_code)]
+pub struct FakeJuce {
+    pub url: String,
+    pub first_commit: Oid,
+    pub second_commit: Oid,
+    pub develop_commit: Oid,
+}
+
+// Create a bare repository at `path` that looks enough like JUCE for jumake:
+// `main` has two commits (tagged `7.0.0` and `8.0.0`), `develop` has a third one on top.
+#[allow(dead_code)]
+pub fn create_fake_juce_repo(path: &Path) -> FakeJuce {
+    let _ = fs::remove_dir_all(path);
+    let repo = Repository::init_bare(path).expect("Failed to init fake JUCE repository");
+    let signature = Signature::now("Test", "test@example.com").unwrap();
+
+    let first_commit = commit_version(&repo, "7.0.0", None, "main");
+    let second_commit = commit_version(&repo, "8.0.0", Some(first_commit), "main");
+    let develop_commit = commit_version(&repo, "8.1.0", Some(second_commit), "develop");
+    repo.set_head("refs/heads/main").unwrap();
+
+    let first = repo.find_object(first_commit, None).unwrap();
+    repo.tag("7.0.0", &first, &signature, "JUCE 7.0.0", false)
+        .unwrap();
+    let second = repo.find_object(second_commit, None).unwrap();
+    repo.tag_lightweight("8.0.0", &second, false).unwrap();
+
+    FakeJuce {
+        url: format!("file://{}", path.display()),
+        first_commit,
+        second_commit,
+        develop_commit,
+    }
+}
+
+// Add a commit declaring JUCE `version` on top of `branch` in the fake JUCE repository.
+#[allow(dead_code)]
+pub fn add_juce_commit(path: &Path, branch: &str, version: &str) -> Oid {
+    let repo = Repository::open_bare(path).unwrap();
+    let parent = repo.refname_to_id(&format!("refs/heads/{}", branch)).ok();
+    commit_version(&repo, version, parent, branch)
+}
+
+// Commit a CMakeLists.txt declaring JUCE `version` on top of `parent` and point `branch` at it.
+fn commit_version(repo: &Repository, version: &str, parent: Option<Oid>, branch: &str) -> Oid {
+    let signature = Signature::now("Test", "test@example.com").unwrap();
+    let content = format!("project(JUCE VERSION {} LANGUAGES C CXX)\n", version);
+    let blob = repo.blob(content.as_bytes()).unwrap();
+    let mut tree_builder = repo.treebuilder(None).unwrap();
+    tree_builder
+        .insert("CMakeLists.txt", blob, 0o100644)
+        .unwrap();
+    let tree = repo.find_tree(tree_builder.write().unwrap()).unwrap();
+    let parents = parent
+        .map(|oid| vec![repo.find_commit(oid).unwrap()])
+        .unwrap_or_default();
+    let parents = parents.iter().collect::<Vec<_>>();
+    let oid = repo
+        .commit(None, &signature, &signature, version, &tree, &parents)
+        .unwrap();
+    repo.reference(&format!("refs/heads/{}", branch), oid, true, "test")
+        .unwrap();
+    oid
+}
+
+// Create a fresh ConsoleApp project named "Tool" at `path` for the `jumake add` tests.
+#[allow(dead_code)]
+pub fn create_console_project(path: &Path) -> Context {
+    let _ = fs::remove_dir_all(path); // Clean up before running the test
+    fs::create_dir_all(path).expect("Failed to create test project directory");
+    let context = Context {
+        project_name: String::from("Tool"),
+        project_path: path.to_path_buf(),
+        template_name: Some(String::from("ConsoleApp")),
+        ..Default::default()
+    };
+    create_source_files(&context).expect("Failed to create source files");
+    context
+}
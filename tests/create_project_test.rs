@@ -0,0 +1,63 @@
+// tests/create_project_test.rs
+
+mod common;
+
+use common::create_fake_juce_repo;
+use git2::Repository;
+use jumake::{context::Context, create_project::create_project};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn entries(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_failed_project_creation_leaves_nothing_behind() {
+    let root = PathBuf::from("/tmp/create_project_rollback_test");
+    let _ = fs::remove_dir_all(&root); // Clean up before running the test
+    let juce = create_fake_juce_repo(&root.join("JUCE.git"));
+    let projects = root.join("projects");
+
+    let mut context = Context {
+        project_name: String::from("Rollback"),
+        project_path: projects.join("Rollback"),
+        template_name: Some(String::from("ConsoleApp")),
+        juce_source: format!("file://{}", root.join("missing.git").display()),
+        ..Default::default()
+    };
+    assert!(create_project(&context).is_err());
+    assert!(entries(&projects).is_empty(), "{:?}", entries(&projects));
+
+    // With --keep-on-failure the staging directory stays for inspection.
+    context.keep_on_failure = true;
+    assert!(create_project(&context).is_err());
+    let kept = entries(&projects);
+    assert_eq!(kept.len(), 1, "{:?}", kept);
+    assert!(kept[0].starts_with(".Rollback.jumake-"), "{:?}", kept);
+    assert!(projects.join(&kept[0]).join("jumake.toml").exists());
+    fs::remove_dir_all(projects.join(&kept[0])).unwrap();
+
+    // Nothing blocks the next attempt.
+    context.keep_on_failure = false;
+    context.juce_source = juce.url.clone();
+    create_project(&context).expect("Failed to create project");
+    assert_eq!(entries(&projects), ["Rollback"]);
+
+    // The JUCE submodule still works after the project was moved into place.
+    let repo = Repository::open(&context.project_path).unwrap();
+    let submodule = repo.find_submodule("modules/JUCE").unwrap();
+    let juce_repo = submodule.open().expect("Failed to open the JUCE submodule");
+    assert_eq!(
+        juce_repo.head().unwrap().peel_to_commit().unwrap().id(),
+        juce.second_commit
+    );
+    assert!(repo.statuses(None).unwrap().is_empty());
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
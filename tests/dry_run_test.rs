@@ -0,0 +1,85 @@
+// tests/dry_run_test.rs
+
+use jumake::{
+    context::Context,
+    create_files::{add_class, create_source_files, ClassOptions},
+    dry_run::{dry_run_add, dry_run_new},
+};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn test_dry_run_new_writes_nothing() {
+    let root = PathBuf::from("/tmp/dry_run_new_test");
+    let _ = fs::remove_dir_all(&root); // Clean up before running the test
+    let context = Context {
+        project_name: String::from("Delay"),
+        project_path: root.join("Delay"),
+        template_name: Some(String::from("AudioPlugin")),
+        juce_revision: Some(String::from("8.0.3")),
+        ..Default::default()
+    };
+
+    let report = dry_run_new(&context).expect("Dry run failed");
+    assert!(!root.exists());
+    for expected in [
+        "  CMakeLists.txt (",
+        "  src/PluginProcessor.cpp (",
+        "  src/CMakeLists.txt (",
+        "  jumake.toml (",
+        "  .gitignore (",
+        "git submodule add https://github.com/juce-framework/JUCE.git modules/JUCE at 8.0.3",
+        "git commit",
+    ] {
+        assert!(
+            report.contains(expected),
+            "Missing '{}' in:\n{}",
+            expected,
+            report
+        );
+    }
+
+    // The same checks as a real run apply.
+    fs::create_dir_all(&context.project_path).unwrap();
+    assert!(dry_run_new(&context).is_err());
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_dry_run_add_shows_cmakelists_diff() {
+    let project_path = PathBuf::from("/tmp/dry_run_add_test");
+    let _ = fs::remove_dir_all(&project_path); // Clean up before running the test
+    fs::create_dir_all(&project_path).unwrap();
+    let context = Context {
+        project_name: String::from("test_project"),
+        project_path: project_path.clone(),
+        template_name: Some(String::from("ConsoleApp")),
+        ..Default::default()
+    };
+    create_source_files(&context).expect("Failed to create source files");
+    let cmakelists_path = project_path.join("src").join("CMakeLists.txt");
+    let before = fs::read_to_string(&cmakelists_path).unwrap();
+
+    let report = dry_run_add(&context, "component", "Knob", &ClassOptions::default())
+        .expect("Dry run failed");
+    assert!(report.contains("  src/KnobComponent.h ("), "{}", report);
+    assert!(report.contains("--- a/src/CMakeLists.txt"), "{}", report);
+    assert!(report.contains("+        KnobComponent.cpp"), "{}", report);
+    assert!(!project_path.join("src").join("KnobComponent.h").exists());
+    assert_eq!(fs::read_to_string(&cmakelists_path).unwrap(), before);
+
+    // The real run makes exactly that change.
+    add_class(&context, "component", "Knob", &ClassOptions::default())
+        .expect("Failed to add component");
+    let after = fs::read_to_string(&cmakelists_path).unwrap();
+    assert_eq!(
+        after,
+        before.replace(
+            "        # JUMAKE_SOURCES_END",
+            "        KnobComponent.cpp\n        # JUMAKE_SOURCES_END"
+        )
+    );
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test directory");
+}
@@ -0,0 +1,113 @@
+// tests/git_template_test.rs
+
+mod common;
+
+use common::create_fake_juce_repo;
+use git2::{Repository, Signature};
+use jumake::{manifest::Manifest, templates::fetch_git_template};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+// Create a template repository whose `v1` tag and `main` branch render different files.
+fn create_template_repo(path: &Path) -> String {
+    let repo = Repository::init(path).expect("Failed to init template repository");
+    let signature = Signature::now("Test", "test@example.com").unwrap();
+    fs::create_dir_all(path.join("src")).unwrap();
+    fs::write(
+        path.join("template.toml"),
+        "[template]\nname = \"GitTemplate\"\n\n[[files]]\nsource = \"src/CMakeLists.txt\"\n",
+    )
+    .unwrap();
+
+    let commit = |version: &str| {
+        fs::write(
+            path.join("src").join("CMakeLists.txt"),
+            format!("# {{{{project_name}}}} from template {}\n", version),
+        )
+        .unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            version,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    };
+    let first = commit("v1");
+    repo.tag_lightweight("v1", &repo.find_object(first, None).unwrap(), false)
+        .unwrap();
+    commit("v2");
+
+    format!("file://{}", path.display())
+}
+
+#[test]
+fn test_fetch_git_template() {
+    let root = PathBuf::from("/tmp/git_template_fetch_test");
+    let _ = fs::remove_dir_all(&root); // Clean up before running the test
+    let url = create_template_repo(&root.join("template-repo"));
+
+    let checkout = root.join("latest");
+    fs::create_dir_all(&checkout).unwrap();
+    let template = fetch_git_template(&url, &checkout).expect("Failed to fetch template");
+    assert_eq!(template.name(), "GitTemplate");
+    assert!(!checkout.join(".git").exists());
+    let cmake = fs::read_to_string(checkout.join("src").join("CMakeLists.txt")).unwrap();
+    assert!(cmake.contains("from template v2"), "{}", cmake);
+
+    let checkout = root.join("tagged");
+    fs::create_dir_all(&checkout).unwrap();
+    fetch_git_template(&format!("{}#v1", url), &checkout).expect("Failed to fetch template");
+    let cmake = fs::read_to_string(checkout.join("src").join("CMakeLists.txt")).unwrap();
+    assert!(cmake.contains("from template v1"), "{}", cmake);
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_new_with_git_template() {
+    let root = PathBuf::from("/tmp/git_template_new_test");
+    let _ = fs::remove_dir_all(&root); // Clean up before running the test
+    let juce = create_fake_juce_repo(&root.join("JUCE.git"));
+    let url = create_template_repo(&root.join("template-repo"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_jumake"))
+        .args(["new", "FromGit", "--no-cache", "--template-git"])
+        .arg(format!("{}#v1", url))
+        .env("JUMAKE_JUCE_URL", &juce.url)
+        .stdin(Stdio::null())
+        .current_dir(&root)
+        .output()
+        .expect("Failed to run jumake");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let project_path = root.join("FromGit");
+    let manifest = Manifest::load(&project_path).unwrap();
+    assert_eq!(manifest.project.template, "GitTemplate");
+    let cmake = fs::read_to_string(project_path.join("src").join("CMakeLists.txt")).unwrap();
+    assert!(cmake.contains("# FromGit from template v1"), "{}", cmake);
+    assert!(!project_path.join("template.toml").exists());
+
+    // Only jumake's initial commit, none of the template's history.
+    let repo = Repository::open(&project_path).unwrap();
+    let mut revwalk = repo.revwalk().unwrap();
+    revwalk.push_head().unwrap();
+    assert_eq!(revwalk.count(), 1);
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
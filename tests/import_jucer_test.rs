@@ -0,0 +1,240 @@
+// tests/import_jucer_test.rs
+
+mod common;
+
+use common::create_fake_juce_repo;
+use jumake::{
+    context::Context, create_project::create_project_from_files, import_jucer::JucerProject,
+    manifest::Manifest, plugin::PluginKind,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const JUCER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+
+<JUCERPROJECT id="a1b2c3" name="Legacy Synth" projectType="audioplug" version="2.1.0"
+              companyName="Acme Audio" companyWebsite="https://acme.example"
+              pluginFormats="buildAU,buildVST3,buildStandalone,buildAAX"
+              pluginCharacteristicsValue="pluginIsSynth,pluginWantsMidiIn,pluginEditorRequiresKeys"
+              pluginManufacturerCode="Acme" pluginCode="Lsy1" bundleIdentifier="com.acme.legacysynth"
+              defines="LEGACY_MODE=1&#10;USE_FAST_MATH" cppLanguageStandard="17"
+              jucerFormatVersion="1">
+  <MAINGROUP id="m1" name="Legacy Synth">
+    <GROUP id="g1" name="Source">
+      <FILE id="f1" name="PluginProcessor.cpp" compile="1" resource="0" file="Source/PluginProcessor.cpp"/>
+      <FILE id="f2" name="PluginProcessor.h" compile="0" resource="0" file="Source/PluginProcessor.h"/>
+      <GROUP id="g2" name="dsp">
+        <FILE id="f3" name="Voice.cpp" compile="1" resource="0" file="Source/dsp/Voice.cpp"/>
+      </GROUP>
+      <FILE id="f4" name="Shared.cpp" compile="1" resource="0" file="../Shared/Shared.cpp"/>
+    </GROUP>
+    <GROUP id="g3" name="Resources">
+      <FILE id="f5" name="logo.png" compile="0" resource="1" file="Resources/logo.png"/>
+    </GROUP>
+  </MAINGROUP>
+  <MODULES>
+    <MODULE id="juce_audio_utils" showAllCode="1" useLocalCopy="0" useGlobalPath="1"/>
+    <MODULE id="juce_dsp" showAllCode="1" useLocalCopy="0" useGlobalPath="1"/>
+    <MODULE id="acme_widgets" showAllCode="1" useLocalCopy="0" useGlobalPath="0"/>
+  </MODULES>
+  <JUCEOPTIONS JUCE_VST3_CAN_REPLACE_VST2="1" JUCE_STRICT_REFCOUNTEDPOINTER="1" JUCE_USE_MP3AUDIOFORMAT="default"/>
+  <EXPORTFORMATS>
+    <XCODE_MAC targetFolder="Builds/MacOSX" extraDefs="MAC_ONLY=1">
+      <CONFIGURATIONS>
+        <CONFIGURATION isDebug="1" name="Debug" defines="TRACE=1" osxCompatibility="10.13 SDK"/>
+        <CONFIGURATION isDebug="0" name="Release"/>
+      </CONFIGURATIONS>
+    </XCODE_MAC>
+    <ANDROIDSTUDIO targetFolder="Builds/Android"/>
+  </EXPORTFORMATS>
+</JUCERPROJECT>
+"#;
+
+fn write_jucer_project(dir: &Path) -> PathBuf {
+    let _ = fs::remove_dir_all(dir); // Clean up before running the test
+    fs::create_dir_all(dir.join("Source").join("dsp")).unwrap();
+    fs::create_dir_all(dir.join("Resources")).unwrap();
+    fs::write(
+        dir.join("Source").join("PluginProcessor.cpp"),
+        "#include \"PluginProcessor.h\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("Source").join("PluginProcessor.h"),
+        "#pragma once\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("Source").join("dsp").join("Voice.cpp"),
+        "// voice\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("Resources").join("logo.png"),
+        [0x89, b'P', b'N', b'G'],
+    )
+    .unwrap();
+    let jucer_path = dir.join("LegacySynth.jucer");
+    fs::write(&jucer_path, JUCER).unwrap();
+    jucer_path
+}
+
+#[test]
+fn test_translate_jucer_project() {
+    let dir = PathBuf::from("/tmp/import_jucer_translate_test");
+    let project = JucerProject::load(&write_jucer_project(&dir)).expect("Failed to load .jucer");
+    assert_eq!(project.name, "Legacy Synth");
+    assert_eq!(project.project_name(), "LegacySynth");
+
+    let context = Context {
+        project_name: String::from("LegacySynth"),
+        project_path: PathBuf::from("/tmp/unused"),
+        ..Default::default()
+    };
+    let import = project.translate(&context).expect("Failed to translate");
+
+    assert_eq!(import.context.template_name.as_deref(), Some("AudioPlugin"));
+    assert_eq!(import.context.version, "2.1.0");
+    assert_eq!(import.context.company.as_deref(), Some("Acme Audio"));
+    let plugin = import.context.plugin.clone().unwrap();
+    assert_eq!(plugin.kind, PluginKind::Synth);
+    assert!(plugin.midi_input);
+    assert!(!plugin.midi_output);
+    assert_eq!(plugin.plugin_code, "Lsy1");
+    assert_eq!(plugin.formats, ["VST3", "AU", "AAX", "Standalone"]);
+
+    let paths: Vec<String> = import
+        .files
+        .iter()
+        .map(|file| file.path.display().to_string())
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            "src/PluginProcessor.cpp",
+            "src/PluginProcessor.h",
+            "src/dsp/Voice.cpp",
+            "src/Resources/logo.png",
+            "src/CMakeLists.txt"
+        ]
+    );
+
+    let cmakelists = String::from_utf8(import.files.last().unwrap().content.clone()).unwrap();
+    for expected in [
+        "# JUMAKE_SOURCES_BEGIN\n        PluginProcessor.cpp\n        dsp/Voice.cpp\n        # JUMAKE_SOURCES_END",
+        "IS_SYNTH TRUE",
+        "FORMATS VST3 AU AAX Standalone",
+        "JUCE_VST3_CAN_REPLACE_VST2=1)",
+        "juce_generate_juce_header(${PROJECT_NAME})",
+        "juce_add_binary_data(${PROJECT_NAME}_BinaryData\n    SOURCES\n        Resources/logo.png)",
+        "        JUCE_STRICT_REFCOUNTEDPOINTER=1\n        LEGACY_MODE=1\n        USE_FAST_MATH\n",
+        "$<$<PLATFORM_ID:Darwin>:MAC_ONLY=1>",
+        "$<$<AND:$<PLATFORM_ID:Darwin>,$<CONFIG:Debug>>:TRACE=1>",
+        "${PROJECT_NAME}_BinaryData\n        juce::juce_dsp)",
+        "target_compile_features(${PROJECT_NAME} PRIVATE cxx_std_17)",
+    ] {
+        assert!(
+            cmakelists.contains(expected),
+            "Missing '{}' in:\n{}",
+            expected,
+            cmakelists
+        );
+    }
+    assert!(!cmakelists.contains("JUCE_USE_MP3AUDIOFORMAT"));
+
+    let report = import.untranslated.join("\n");
+    for expected in [
+        "companyWebsite",
+        "pluginEditorRequiresKeys",
+        "../Shared/Shared.cpp",
+        "acme_widgets",
+        "exporter ANDROIDSTUDIO",
+        "exporter XCODE_MAC configuration Debug settings: osxCompatibility",
+    ] {
+        assert!(
+            report.contains(expected),
+            "Missing '{}' in:\n{}",
+            expected,
+            report
+        );
+    }
+
+    fs::remove_dir_all(&dir).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_translate_library_project() {
+    let dir = PathBuf::from("/tmp/import_jucer_library_test");
+    let _ = fs::remove_dir_all(&dir); // Clean up before running the test
+    fs::create_dir_all(dir.join("Source")).unwrap();
+    fs::write(dir.join("Source").join("Library.cpp"), "// library\n").unwrap();
+    let jucer_path = dir.join("Shared.jucer");
+    fs::write(
+        &jucer_path,
+        r#"<JUCERPROJECT id="l1" name="Shared" projectType="library" version="1.0.0">
+  <MAINGROUP id="m1" name="Shared">
+    <FILE id="f1" name="Library.cpp" compile="1" resource="0" file="Source/Library.cpp"/>
+  </MAINGROUP>
+  <MODULES>
+    <MODULE id="juce_core" showAllCode="1" useLocalCopy="0" useGlobalPath="1"/>
+  </MODULES>
+</JUCERPROJECT>
+"#,
+    )
+    .unwrap();
+
+    let project = JucerProject::load(&jucer_path).expect("Failed to load .jucer");
+    let context = Context {
+        project_name: String::from("Shared"),
+        project_path: PathBuf::from("/tmp/unused"),
+        ..Default::default()
+    };
+    let import = project.translate(&context).expect("Failed to translate");
+    assert_eq!(
+        import.context.template_name.as_deref(),
+        Some("StaticLibrary")
+    );
+
+    // The library is a plain add_library target, which JUCE can't generate a JuceHeader.h for.
+    let cmakelists = String::from_utf8(import.files.last().unwrap().content.clone()).unwrap();
+    assert!(cmakelists.contains("add_library(${PROJECT_NAME} STATIC)"));
+    assert!(
+        !cmakelists.contains("juce_generate_juce_header"),
+        "{}",
+        cmakelists
+    );
+    let report = import.untranslated.join("\n");
+    assert!(report.contains("JuceHeader.h"), "{}", report);
+
+    fs::remove_dir_all(&dir).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_import_creates_project() {
+    let root = PathBuf::from("/tmp/import_jucer_create_test");
+    let jucer_path = write_jucer_project(&root.join("legacy"));
+    let juce = create_fake_juce_repo(&root.join("JUCE.git"));
+
+    let project = JucerProject::load(&jucer_path).unwrap();
+    let context = Context {
+        project_name: String::from("LegacySynth"),
+        project_path: root.join("LegacySynth"),
+        juce_source: juce.url.clone(),
+        ..Default::default()
+    };
+    let import = project.translate(&context).unwrap();
+    create_project_from_files(&import.context, &import.files).expect("Failed to create project");
+
+    let project_path = &context.project_path;
+    assert_eq!(
+        fs::read(project_path.join("src").join("Resources").join("logo.png")).unwrap(),
+        [0x89, b'P', b'N', b'G']
+    );
+    assert!(project_path.join("modules").join("JUCE").exists());
+    let manifest = Manifest::load(project_path).unwrap();
+    assert_eq!(manifest.project.template, "AudioPlugin");
+    assert_eq!(manifest.project.version, "2.1.0");
+    assert_eq!(manifest.plugin, import.context.plugin);
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
@@ -0,0 +1,195 @@
+// tests/init_test.rs
+
+mod common;
+
+use common::create_fake_juce_repo;
+use git2::Repository;
+use jumake::{
+    context::Context,
+    create_files::{add_class, add_source_markers, ClassOptions},
+    init_project::{detect_project, init_project},
+    manifest::Manifest,
+};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn test_add_source_markers() {
+    let content = "juce_add_gui_app(App)\n\ntarget_sources(App\n    PRIVATE\n        Source/Main.cpp\n        Source/MainComponent.cpp)\n";
+    let marked = add_source_markers(content).expect("Failed to insert markers");
+    assert_eq!(
+        marked,
+        "juce_add_gui_app(App)\n\ntarget_sources(App\n    PRIVATE\n        # JUMAKE_SOURCES_BEGIN\n        Source/Main.cpp\n        Source/MainComponent.cpp\n        # JUMAKE_SOURCES_END\n)\n"
+    );
+    assert!(add_source_markers(&marked).is_none());
+
+    // A source list on the PRIVATE line cannot be wrapped.
+    assert!(add_source_markers("target_sources(App PRIVATE Main.cpp)\n").is_none());
+}
+
+#[test]
+fn test_init_empty_directory() {
+    let root = PathBuf::from("/tmp/init_empty_directory_test");
+    let _ = fs::remove_dir_all(&root); // Clean up before running the test
+    let juce = create_fake_juce_repo(&root.join("JUCE.git"));
+    let project_path = root.join("Tool");
+    fs::create_dir_all(&project_path).unwrap();
+    fs::write(project_path.join("README.md"), "# Tool\n").unwrap();
+
+    assert!(detect_project(&project_path).unwrap().is_none());
+    let context = Context {
+        project_name: String::from("Tool"),
+        project_path: project_path.clone(),
+        template_name: Some(String::from("ConsoleApp")),
+        juce_source: juce.url.clone(),
+        ..Default::default()
+    };
+    init_project(&context).expect("Failed to initialize project");
+
+    assert!(project_path.join("CMakeLists.txt").exists());
+    assert!(project_path.join("src").join("Main.cpp").exists());
+    assert_eq!(
+        Manifest::load(&project_path).unwrap().project.template,
+        "ConsoleApp"
+    );
+    let repo = Repository::open(&project_path).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert!(head.tree().unwrap().get_name("README.md").is_some());
+    assert!(repo.find_submodule("modules/JUCE").is_ok());
+
+    // A second init is refused.
+    assert!(init_project(&context).is_err());
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_init_existing_cmake_project() {
+    let root = PathBuf::from("/tmp/init_existing_project_test");
+    let _ = fs::remove_dir_all(&root); // Clean up before running the test
+    let juce_path = root.join("JUCE");
+    fs::create_dir_all(&juce_path).unwrap();
+    fs::write(juce_path.join("CMakeLists.txt"), "project(JUCE)\n").unwrap();
+
+    let project_path = root.join("Synth");
+    fs::create_dir_all(project_path.join("src")).unwrap();
+    fs::write(
+        project_path.join("CMakeLists.txt"),
+        "cmake_minimum_required(VERSION 3.22)\nproject(Synth VERSION 1.2.3)\n\nadd_subdirectory(src)\n",
+    )
+    .unwrap();
+    fs::write(
+        project_path.join("src").join("CMakeLists.txt"),
+        "juce_add_plugin(Synth\n    IS_SYNTH TRUE)\n\ntarget_sources(Synth\n    PRIVATE\n        PluginProcessor.cpp)\n",
+    )
+    .unwrap();
+    fs::write(project_path.join(".gitignore"), "build/\n").unwrap();
+    Repository::init(&project_path).unwrap();
+
+    let existing = detect_project(&project_path).unwrap().unwrap();
+    assert_eq!(existing.name.as_deref(), Some("Synth"));
+    assert_eq!(existing.version.as_deref(), Some("1.2.3"));
+    assert_eq!(existing.template_name.as_deref(), Some("AudioPlugin"));
+    assert!(!existing.provides_juce);
+
+    let context = Context {
+        project_name: String::from("Synth"),
+        project_path: project_path.clone(),
+        template_name: existing.template_name.clone(),
+        version: String::from("1.2.3"),
+        juce_path: Some(juce_path.clone()),
+        ..Default::default()
+    };
+    init_project(&context).expect("Failed to initialize project");
+
+    let root_cmakelists = fs::read_to_string(project_path.join("CMakeLists.txt")).unwrap();
+    assert_eq!(
+        root_cmakelists,
+        format!(
+            "cmake_minimum_required(VERSION 3.22)\nproject(Synth VERSION 1.2.3)\nadd_subdirectory(\"{}\" ${{CMAKE_BINARY_DIR}}/JUCE)\n\nadd_subdirectory(src)\n",
+            juce_path.display()
+        )
+    );
+    let src_cmakelists =
+        fs::read_to_string(project_path.join("src").join("CMakeLists.txt")).unwrap();
+    assert!(
+        src_cmakelists.starts_with("set(JUMAKE_TEMPLATE \"AudioPlugin\")\n\njuce_add_plugin"),
+        "{}",
+        src_cmakelists
+    );
+    assert!(src_cmakelists.contains("# JUMAKE_SOURCES_BEGIN"));
+    let gitignore = fs::read_to_string(project_path.join(".gitignore")).unwrap();
+    assert_eq!(gitignore.matches("build/").count(), 2, "{}", gitignore); // build/ and jumake_build/
+
+    // The existing repository gets the changes staged, not committed.
+    let repo = Repository::open(&project_path).unwrap();
+    assert!(repo.head().is_err());
+    let index = repo.index().unwrap();
+    for path in [
+        "CMakeLists.txt",
+        "src/CMakeLists.txt",
+        "jumake.toml",
+        ".gitignore",
+    ] {
+        assert!(
+            index.get_path(std::path::Path::new(path), 0).is_some(),
+            "{}",
+            path
+        );
+    }
+    assert!(!project_path.join("modules").exists());
+
+    // `jumake add` uses the new markers.
+    add_class(&context, "class", "Voice", &ClassOptions::default()).expect("Failed to add class");
+    let src_cmakelists =
+        fs::read_to_string(project_path.join("src").join("CMakeLists.txt")).unwrap();
+    assert!(
+        src_cmakelists
+            .contains("PluginProcessor.cpp\n        Voice.cpp\n        # JUMAKE_SOURCES_END\n)"),
+        "{}",
+        src_cmakelists
+    );
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_init_marks_the_target_call() {
+    let root = PathBuf::from("/tmp/init_target_marker_test");
+    let _ = fs::remove_dir_all(&root); // Clean up before running the test
+    let juce_path = root.join("JUCE");
+    fs::create_dir_all(&juce_path).unwrap();
+    fs::write(juce_path.join("CMakeLists.txt"), "project(JUCE)\n").unwrap();
+
+    // Binary data and modules are often declared above the app itself.
+    let project_path = root.join("Viewer");
+    fs::create_dir_all(&project_path).unwrap();
+    fs::write(
+        project_path.join("CMakeLists.txt"),
+        "cmake_minimum_required(VERSION 3.22)\nproject(Viewer VERSION 1.0.0)\n\njuce_add_binary_data(ViewerData SOURCES logo.png)\n\njuce_add_gui_app(Viewer)\n\ntarget_sources(Viewer\n    PRIVATE\n        Main.cpp)\n",
+    )
+    .unwrap();
+
+    let existing = detect_project(&project_path).unwrap().unwrap();
+    assert_eq!(existing.template_name.as_deref(), Some("GuiApplication"));
+    let context = Context {
+        project_name: String::from("Viewer"),
+        project_path: project_path.clone(),
+        template_name: existing.template_name.clone(),
+        version: String::from("1.0.0"),
+        juce_path: Some(juce_path),
+        ..Default::default()
+    };
+    init_project(&context).expect("Failed to initialize project");
+
+    let cmakelists = fs::read_to_string(project_path.join("CMakeLists.txt")).unwrap();
+    assert!(
+        cmakelists.contains(
+            "juce_add_binary_data(ViewerData SOURCES logo.png)\n\nset(JUMAKE_TEMPLATE \"GuiApplication\")\n\njuce_add_gui_app(Viewer)\n"
+        ),
+        "{}",
+        cmakelists
+    );
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
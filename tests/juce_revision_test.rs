@@ -0,0 +1,192 @@
+// tests/juce_revision_test.rs
+
+mod common;
+
+use common::create_fake_juce_repo;
+use git2::Repository;
+use jumake::{
+    context::{resolve_juce_url, Context, DEFAULT_JUCE_URL, JUCE_URL_ENV_VAR},
+    create_project::create_project,
+    initialize_git::fetch_revision,
+    manifest::Manifest,
+};
+use std::fs;
+use std::path::PathBuf;
+
+fn juce_version(repo_path: &std::path::Path) -> String {
+    fs::read_to_string(repo_path.join("CMakeLists.txt")).expect("Failed to read JUCE CMakeLists")
+}
+
+#[test]
+fn test_fetch_revision_resolves_tags_branches_and_commits() {
+    let root = PathBuf::from("/tmp/juce_revision_fetch_test");
+    let _ = fs::remove_dir_all(&root);
+    let juce = create_fake_juce_repo(&root.join("JUCE.git"));
+
+    let cases = [
+        (Some("7.0.0"), juce.first_commit, None),
+        (Some("8.0.0"), juce.second_commit, None),
+        (Some("develop"), juce.develop_commit, Some("develop")),
+        (None, juce.second_commit, Some("main")),
+    ];
+    for (index, (revision, expected_commit, expected_branch)) in cases.iter().enumerate() {
+        let checkout_path = root.join(format!("checkout{}", index));
+        let repo = Repository::init(&checkout_path).unwrap();
+        let fetched =
+            fetch_revision(&repo, &juce.url, *revision).expect("Failed to fetch revision");
+        assert_eq!(fetched.commit, *expected_commit);
+        assert_eq!(fetched.branch.as_deref(), *expected_branch);
+        assert_eq!(
+            repo.head().unwrap().peel_to_commit().unwrap().id(),
+            *expected_commit
+        );
+    }
+
+    let repo = Repository::init(root.join("checkout_sha")).unwrap();
+    let sha = juce.first_commit.to_string();
+    let fetched = fetch_revision(&repo, &juce.url, Some(&sha)).expect("Failed to fetch commit");
+    assert_eq!(fetched.commit, juce.first_commit);
+    assert!(juce_version(&root.join("checkout_sha")).contains("VERSION 7.0.0"));
+
+    let repo = Repository::init(root.join("checkout_missing")).unwrap();
+    assert!(fetch_revision(&repo, &juce.url, Some("9.9.9")).is_err());
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_create_project_pins_juce_version() {
+    let root = PathBuf::from("/tmp/juce_revision_project_test");
+    let _ = fs::remove_dir_all(&root);
+    let juce = create_fake_juce_repo(&root.join("JUCE.git"));
+
+    let context = Context {
+        project_name: String::from("PinnedProject"),
+        project_path: root.join("PinnedProject"),
+        template_name: Some(String::from("ConsoleApp")),
+        juce_source: juce.url.clone(),
+        juce_revision: Some(String::from("7.0.0")),
+        ..Default::default()
+    };
+    create_project(&context).expect("Failed to create project");
+
+    let juce_path = context.project_path.join("modules").join("JUCE");
+    assert!(juce_version(&juce_path).contains("VERSION 7.0.0"));
+
+    let manifest = Manifest::load(&context.project_path).unwrap();
+    assert_eq!(manifest.juce.revision.as_deref(), Some("7.0.0"));
+    assert_eq!(manifest.juce.source, juce.url);
+
+    let repo = Repository::open(&context.project_path).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert!(head
+        .message()
+        .unwrap()
+        .contains(&juce.first_commit.to_string()));
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_create_project_with_external_juce_path() {
+    let root = PathBuf::from("/tmp/juce_path_project_test");
+    let _ = fs::remove_dir_all(&root);
+    let juce_path = root.join("SharedJUCE");
+    fs::create_dir_all(&juce_path).unwrap();
+    fs::write(
+        juce_path.join("CMakeLists.txt"),
+        "project(JUCE VERSION 8.0.0)\n",
+    )
+    .unwrap();
+
+    let context = Context {
+        project_name: String::from("ExternalJuce"),
+        project_path: root.join("ExternalJuce"),
+        template_name: Some(String::from("ConsoleApp")),
+        juce_path: Some(juce_path.clone()),
+        ..Default::default()
+    };
+    create_project(&context).expect("Failed to create project");
+
+    assert!(!context.project_path.join("modules").join("JUCE").exists());
+    assert!(!context.project_path.join(".gitmodules").exists());
+    let cmakelists = fs::read_to_string(context.project_path.join("CMakeLists.txt")).unwrap();
+    assert!(cmakelists.contains(&format!(
+        "add_subdirectory(\"{}\" ${{CMAKE_BINARY_DIR}}/JUCE)",
+        juce_path.display()
+    )));
+    let manifest = Manifest::load(&context.project_path).unwrap();
+    assert_eq!(manifest.juce.path, Some(juce_path));
+
+    let missing = Context {
+        project_name: String::from("MissingJuce"),
+        project_path: root.join("MissingJuce"),
+        template_name: Some(String::from("ConsoleApp")),
+        juce_path: Some(root.join("DoesNotExist")),
+        ..Default::default()
+    };
+    assert!(create_project(&missing).is_err());
+    assert!(!missing.project_path.exists());
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_resolve_juce_url_prefers_command_line_over_environment() {
+    std::env::set_var(JUCE_URL_ENV_VAR, "file:///srv/mirrors/JUCE.git");
+    assert_eq!(resolve_juce_url(None), "file:///srv/mirrors/JUCE.git");
+    assert_eq!(
+        resolve_juce_url(Some(String::from("https://example.com/JUCE.git"))),
+        "https://example.com/JUCE.git"
+    );
+    std::env::remove_var(JUCE_URL_ENV_VAR);
+    assert_eq!(resolve_juce_url(None), DEFAULT_JUCE_URL);
+}
+
+#[test]
+fn test_juce_is_registered_as_submodule() {
+    let root = PathBuf::from("/tmp/juce_submodule_project_test");
+    let _ = fs::remove_dir_all(&root);
+    let juce = create_fake_juce_repo(&root.join("JUCE.git"));
+
+    let context = Context {
+        project_name: String::from("SubmoduleProject"),
+        project_path: root.join("SubmoduleProject"),
+        template_name: Some(String::from("ConsoleApp")),
+        juce_source: juce.url.clone(),
+        juce_revision: Some(String::from("develop")),
+        ..Default::default()
+    };
+    create_project(&context).expect("Failed to create project");
+
+    // The initial commit contains a gitlink pointing at the checked-out JUCE commit.
+    let repo = Repository::open(&context.project_path).unwrap();
+    let tree = repo.head().unwrap().peel_to_tree().unwrap();
+    let gitlink = tree
+        .get_path(std::path::Path::new("modules/JUCE"))
+        .expect("modules/JUCE is not part of the initial commit");
+    assert_eq!(gitlink.filemode(), 0o160000);
+    assert_eq!(gitlink.id(), juce.develop_commit);
+    assert!(tree.get_path(std::path::Path::new(".gitmodules")).is_ok());
+
+    let gitmodules = fs::read_to_string(context.project_path.join(".gitmodules")).unwrap();
+    assert!(gitmodules.contains(&format!("url = {}", juce.url)));
+    assert!(gitmodules.contains("branch = develop"));
+    let gitignore = fs::read_to_string(context.project_path.join(".gitignore")).unwrap();
+    assert!(!gitignore.contains("modules/"));
+
+    // A fresh clone of the project gets exactly the same JUCE via `submodule update --init`.
+    let clone_path = root.join("Clone");
+    let clone = Repository::clone(
+        &format!("file://{}", context.project_path.display()),
+        &clone_path,
+    )
+    .expect("Failed to clone generated project");
+    let mut submodule = clone.find_submodule("modules/JUCE").unwrap();
+    submodule
+        .update(true, None)
+        .expect("Failed to update submodule");
+    assert!(juce_version(&clone_path.join("modules").join("JUCE")).contains("VERSION 8.1.0"));
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
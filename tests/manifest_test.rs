@@ -0,0 +1,104 @@
+// tests/manifest_test.rs
+
+use jumake::{
+    context::Context,
+    manifest::{
+        load_project, read_last_build_type, save_last_build_type, Manifest, MANIFEST_FILE_NAME,
+    },
+};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn test_manifest_round_trip() {
+    let project_path = PathBuf::from("/tmp/manifest_round_trip_project");
+    let _ = fs::remove_dir_all(&project_path); // Clean up before running the test
+    fs::create_dir_all(&project_path).expect("Failed to create test project directory");
+
+    let context = Context {
+        project_name: String::from("test_project"),
+        project_path: project_path.clone(),
+        template_name: Some(String::from("AudioPlugin")),
+        company: Some(String::from("Acme")),
+        juce_revision: Some(String::from("8.0.3")),
+        ..Default::default()
+    };
+    let manifest = Manifest::from_context(&context);
+    manifest
+        .save(&project_path)
+        .expect("Failed to save manifest");
+
+    let loaded = load_project(&project_path).expect("Failed to load manifest");
+    assert_eq!(loaded, manifest);
+
+    let loaded_context = loaded.into_context(project_path.clone());
+    assert_eq!(loaded_context.project_name, "test_project");
+    assert_eq!(loaded_context.template_name.as_deref(), Some("AudioPlugin"));
+    assert_eq!(loaded_context.build_dir, "jumake_build");
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
+
+#[test]
+fn test_legacy_project_is_migrated() {
+    let project_path = PathBuf::from("/tmp/manifest_legacy_project");
+    let _ = fs::remove_dir_all(&project_path); // Clean up before running the test
+    fs::create_dir_all(project_path.join("src")).expect("Failed to create test project directory");
+
+    fs::write(
+        project_path.join("CMakeLists.txt"),
+        "cmake_minimum_required(VERSION 3.24)\nproject(LegacyProject VERSION 1.2.3)\n",
+    )
+    .unwrap();
+    fs::write(
+        project_path.join("src").join("CMakeLists.txt"),
+        "set(JUMAKE_TEMPLATE \"ConsoleApp\")\n",
+    )
+    .unwrap();
+    fs::write(project_path.join(".jumake"), "Debug").unwrap();
+
+    let manifest = load_project(&project_path).expect("Failed to migrate legacy project");
+    assert_eq!(manifest.project.name, "LegacyProject");
+    assert_eq!(manifest.project.version, "1.2.3");
+    assert_eq!(manifest.project.template, "ConsoleApp");
+    // The last build type stays state, not the configured default.
+    assert_eq!(manifest.build.default_type, "Release");
+    assert!(project_path.join(MANIFEST_FILE_NAME).exists());
+    assert!(!project_path.join(".jumake").exists());
+    let context = manifest.into_context(project_path.clone());
+    assert_eq!(read_last_build_type(&context).as_deref(), Some("Debug"));
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
+
+#[test]
+fn test_last_build_type_does_not_touch_manifest() {
+    let project_path = PathBuf::from("/tmp/manifest_last_build_type_project");
+    let _ = fs::remove_dir_all(&project_path); // Clean up before running the test
+    fs::create_dir_all(&project_path).expect("Failed to create test project directory");
+    let context = Context {
+        project_name: String::from("LastBuild"),
+        project_path: project_path.clone(),
+        template_name: Some(String::from("ConsoleApp")),
+        ..Default::default()
+    };
+    Manifest::from_context(&context)
+        .save(&project_path)
+        .unwrap();
+    let manifest = fs::read_to_string(project_path.join(MANIFEST_FILE_NAME)).unwrap();
+    assert_eq!(read_last_build_type(&context), None);
+
+    let debug = Context {
+        build_type: String::from("Debug"),
+        ..context.clone()
+    };
+    save_last_build_type(&debug).expect("Failed to save last build type");
+    assert_eq!(read_last_build_type(&context).as_deref(), Some("Debug"));
+    assert_eq!(
+        fs::read_to_string(project_path.join(MANIFEST_FILE_NAME)).unwrap(),
+        manifest
+    );
+    assert!(project_path.join(&context.build_dir).is_dir());
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
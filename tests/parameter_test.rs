@@ -0,0 +1,161 @@
+// tests/parameter_test.rs
+
+use jumake::{
+    apvts::{add_parameter, plan_parameter, ParameterControl, ParameterSpec, ParameterType},
+    context::Context,
+    create_files::create_source_files,
+    plugin::PluginOptions,
+};
+use std::fs;
+use std::path::PathBuf;
+
+fn create_plugin(project_path: &PathBuf) -> Context {
+    let _ = fs::remove_dir_all(project_path); // Clean up before running the test
+    fs::create_dir_all(project_path).expect("Failed to create test project directory");
+    let mut plugin = PluginOptions::defaults("Filter", None);
+    plugin.apvts = true;
+    let context = Context {
+        project_name: String::from("Filter"),
+        project_path: project_path.clone(),
+        template_name: Some(String::from("AudioPlugin")),
+        plugin: Some(plugin),
+        ..Default::default()
+    };
+    create_source_files(&context).expect("Failed to create source files");
+    context
+}
+
+fn spec(id: &str, parameter_type: ParameterType) -> ParameterSpec {
+    ParameterSpec {
+        id: id.to_string(),
+        parameter_type,
+        range: None,
+        default: None,
+        choices: Vec::new(),
+        attach: None,
+    }
+}
+
+#[test]
+fn test_add_parameters() {
+    let project_path = PathBuf::from("/tmp/add_parameter_test_project");
+    let context = create_plugin(&project_path);
+    let read = |name: &str| fs::read_to_string(project_path.join("src").join(name)).unwrap();
+
+    add_parameter(
+        &context,
+        &ParameterSpec {
+            range: Some(String::from("20:20000")),
+            default: Some(String::from("1000")),
+            attach: Some(ParameterControl::Slider),
+            ..spec("filterCutoff", ParameterType::Float)
+        },
+    )
+    .expect("Failed to add float parameter");
+    add_parameter(
+        &context,
+        &ParameterSpec {
+            choices: vec![String::from("Low Pass"), String::from("High Pass")],
+            default: Some(String::from("High Pass")),
+            attach: Some(ParameterControl::Combo),
+            ..spec("mode", ParameterType::Choice)
+        },
+    )
+    .expect("Failed to add choice parameter");
+    add_parameter(
+        &context,
+        &ParameterSpec {
+            default: Some(String::from("true")),
+            ..spec("bypass", ParameterType::Bool)
+        },
+    )
+    .expect("Failed to add bool parameter");
+    add_parameter(
+        &context,
+        &ParameterSpec {
+            range: Some(String::from("-2:8")),
+            ..spec("octave", ParameterType::Int)
+        },
+    )
+    .expect("Failed to add int parameter");
+
+    let header = read("PluginProcessor.h");
+    assert!(header.contains(
+        "    inline constexpr auto gain = \"gain\";\n    inline constexpr auto filterCutoff = \"filterCutoff\";\n    inline constexpr auto mode = \"mode\";\n    inline constexpr auto bypass = \"bypass\";\n    inline constexpr auto octave = \"octave\";\n    // JUMAKE_PARAMETER_IDS_END"
+    ), "{}", header);
+
+    let processor = read("PluginProcessor.cpp");
+    for expected in [
+        "layout.add (std::make_unique<juce::AudioParameterFloat> (juce::ParameterID { ParameterIDs::filterCutoff, 1 }, \"Filter Cutoff\",\n                                                             juce::NormalisableRange<float> (20.0f, 20000.0f), 1000.0f));",
+        "juce::StringArray { \"Low Pass\", \"High Pass\" }, 1));",
+        "layout.add (std::make_unique<juce::AudioParameterBool> (juce::ParameterID { ParameterIDs::bypass, 1 }, \"Bypass\", true));",
+        "(juce::ParameterID { ParameterIDs::octave, 1 }, \"Octave\",\n                                                           -2, 8, -2));\n    // JUMAKE_PARAMETERS_END",
+    ] {
+        assert!(processor.contains(expected), "Missing '{}' in:\n{}", expected, processor);
+    }
+
+    let editor_header = read("PluginEditor.h");
+    assert!(editor_header.contains(
+        "    juce::Slider filterCutoffSlider;\n    std::unique_ptr<juce::AudioProcessorValueTreeState::SliderAttachment> filterCutoffAttachment;\n    juce::ComboBox modeBox;\n"
+    ), "{}", editor_header);
+    let editor = read("PluginEditor.cpp");
+    for expected in [
+        "    addAndMakeVisible (filterCutoffSlider);\n",
+        "std::make_unique<juce::AudioProcessorValueTreeState::ComboBoxAttachment> (processorRef.parameters, ParameterIDs::mode, modeBox);\n    // JUMAKE_CONTROLS_END",
+        "    // JUMAKE_LAYOUT_BEGIN\n    auto controls = getLocalBounds().reduced (20);\n    filterCutoffSlider.setBounds (controls.removeFromTop (40));\n    modeBox.setBounds (controls.removeFromTop (40));\n    // JUMAKE_LAYOUT_END",
+    ] {
+        assert!(editor.contains(expected), "Missing '{}' in:\n{}", expected, editor);
+    }
+    assert!(!editor.contains("bypassButton"));
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
+
+#[test]
+fn test_invalid_parameters() {
+    let project_path = PathBuf::from("/tmp/invalid_parameter_test_project");
+    let context = create_plugin(&project_path);
+
+    let invalid = [
+        spec("gain", ParameterType::Bool),
+        spec("2fast", ParameterType::Bool),
+        spec("int", ParameterType::Bool),
+        spec("class", ParameterType::Float),
+        spec("cutoff", ParameterType::Float),
+        ParameterSpec {
+            range: Some(String::from("10:1")),
+            ..spec("cutoff", ParameterType::Float)
+        },
+        ParameterSpec {
+            range: Some(String::from("0:1")),
+            default: Some(String::from("2")),
+            ..spec("cutoff", ParameterType::Float)
+        },
+        ParameterSpec {
+            range: Some(String::from("0:1")),
+            ..spec("enabled", ParameterType::Bool)
+        },
+        spec("mode", ParameterType::Choice),
+        ParameterSpec {
+            attach: Some(ParameterControl::Slider),
+            ..spec("enabled", ParameterType::Bool)
+        },
+    ];
+    for spec in &invalid {
+        assert!(plan_parameter(&context, spec).is_err(), "{:?}", spec);
+    }
+
+    // Projects without the APVTS scaffolding have no regions to edit.
+    let plain = Context {
+        plugin: Some(PluginOptions::defaults("Filter", None)),
+        ..context.clone()
+    };
+    create_source_files(&plain).unwrap();
+    let error = plan_parameter(&plain, &spec("enabled", ParameterType::Bool))
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(error.contains("--with-apvts"), "{}", error);
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
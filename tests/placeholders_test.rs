@@ -0,0 +1,87 @@
+// tests/placeholders_test.rs
+
+use jumake::{
+    context::Context,
+    create_files::{add_class, create_source_files, ClassOptions},
+    placeholders::{class_variables, project_variables, render, Variables},
+};
+use std::fs;
+use std::path::PathBuf;
+
+fn variables(pairs: &[(&str, &str)]) -> Variables {
+    pairs
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_render_placeholders() {
+    let variables = variables(&[("class_name", "Delay"), ("company", "Acme")]);
+
+    let rendered = render(
+        "class {{class_name}} // by {{ company }}, a TemplateClass ${PROJECT_NAME}",
+        &variables,
+        "Class.h",
+    )
+    .expect("Failed to render template");
+    assert_eq!(
+        rendered,
+        "class Delay // by Acme, a TemplateClass ${PROJECT_NAME}"
+    );
+
+    let error = render("line one\n{{class_nme}} {{yaer}}", &variables, "Class.h")
+        .expect_err("Unknown placeholders must be reported");
+    assert!(error.contains("Class.h"), "{}", error);
+    assert!(error.contains("{{class_nme}} (line 2)"), "{}", error);
+    assert!(error.contains("{{yaer}} (line 2)"), "{}", error);
+}
+
+#[test]
+fn test_project_and_class_variables() {
+    let context = Context {
+        project_name: String::from("My Synth"),
+        company: Some(String::from("Acme")),
+        template_name: Some(String::from("AudioPlugin")),
+        ..Default::default()
+    };
+    let variables = project_variables(&context);
+    assert_eq!(variables["project_name"], "My Synth");
+    assert_eq!(variables["company"], "Acme");
+    assert_eq!(variables["namespace"], "my_synth");
+    assert!(variables["year"].parse::<u32>().unwrap() >= 2024);
+    assert_eq!(variables["plugin_code"], "Mysy");
+
+    let variables = class_variables(&context, "Filter");
+    assert_eq!(variables["class_name"], "Filter");
+    assert_eq!(variables["project_name"], "My Synth");
+}
+
+#[test]
+fn test_add_class_keeps_template_word() {
+    let project_path = PathBuf::from("/tmp/placeholders_add_class_project");
+    let _ = fs::remove_dir_all(&project_path); // Clean up before running the test
+    fs::create_dir_all(&project_path).expect("Failed to create test project directory");
+
+    let context = Context {
+        project_name: String::from("test_project"),
+        project_path: project_path.clone(),
+        template_name: Some(String::from("ConsoleApp")),
+        ..Default::default()
+    };
+    create_source_files(&context).expect("Failed to create source files");
+    add_class(&context, "class", "TemplateCache", &ClassOptions::default())
+        .expect("Failed to add class");
+
+    let header = fs::read_to_string(project_path.join("src").join("TemplateCache.h")).unwrap();
+    assert!(header.contains("class TemplateCache"), "{}", header);
+    assert!(!header.contains("{{"), "{}", header);
+    let source = fs::read_to_string(project_path.join("src").join("TemplateCache.cpp")).unwrap();
+    assert!(
+        source.contains("TemplateCache::TemplateCache()"),
+        "{}",
+        source
+    );
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
@@ -0,0 +1,273 @@
+// tests/plugin_test.rs
+
+use jumake::{
+    context::Context,
+    create_files::{create_source_files, generate_source_files, GeneratedFile},
+    manifest::Manifest,
+    plugin::{
+        garage_band_warning, validate_bundle_id, validate_company_name, validate_formats,
+        validate_manufacturer_code, validate_midi, validate_plugin_code, PluginKind, PluginOptions,
+    },
+};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn test_plugin_code_validation() {
+    assert!(validate_manufacturer_code("Acme").is_ok());
+    assert!(validate_manufacturer_code("ACME").is_ok());
+    assert!(validate_manufacturer_code("acme").is_err());
+    assert!(validate_manufacturer_code("Acm").is_err());
+    assert!(validate_manufacturer_code("Ac-e").is_err());
+
+    assert!(validate_plugin_code("Dem0").is_ok());
+    assert!(validate_plugin_code("DEM0").is_err());
+    assert!(validate_plugin_code("dem0").is_err());
+    assert!(validate_plugin_code("Demo1").is_err());
+
+    assert!(garage_band_warning("Dem0").is_none());
+    assert!(garage_band_warning("dEm0").is_some());
+
+    assert!(validate_bundle_id("com.acme.delay").is_ok());
+    assert!(validate_bundle_id("com..delay").is_err());
+    assert!(validate_bundle_id("com.acme delay").is_err());
+
+    assert!(validate_company_name("Acme Audio & Co.").is_ok());
+    assert!(validate_company_name("Foo \"Bar\" Audio").is_err());
+    assert!(validate_company_name("Foo\\Audio").is_err());
+    assert!(validate_company_name("Foo\nAudio").is_err());
+
+    assert!(validate_formats(&[String::from("VST3"), String::from("AU")]).is_ok());
+    assert!(validate_formats(&[String::from("VST4")]).is_err());
+    assert!(validate_formats(&[]).is_err());
+
+    assert!(validate_midi(PluginKind::MidiEffect, true, true).is_ok());
+    assert!(validate_midi(PluginKind::MidiEffect, false, true).is_err());
+    assert!(validate_midi(PluginKind::MidiEffect, true, false).is_err());
+    assert!(validate_midi(PluginKind::Effect, false, false).is_ok());
+    let options = PluginOptions {
+        kind: PluginKind::MidiEffect,
+        midi_input: false,
+        ..PluginOptions::defaults("Arp", None)
+    };
+    assert!(options.validate().is_err());
+}
+
+#[test]
+fn test_plugin_defaults_are_valid() {
+    for (project_name, company) in [
+        ("MyPlugin", Some("Acme Audio")),
+        ("x", None),
+        ("42Delay", Some("A")),
+        ("Foo", Some("42 Audio")),
+        ("Foo", Some("!!! ???")),
+    ] {
+        let options = PluginOptions::defaults(project_name, company);
+        assert!(
+            options.validate().is_ok(),
+            "Invalid defaults for {}: {:?}",
+            project_name,
+            options
+        );
+    }
+
+    let options = PluginOptions::defaults("MyPlugin", Some("Acme Audio"));
+    assert_eq!(options.manufacturer_code, "Acme");
+    assert_eq!(options.plugin_code, "Mypl");
+    assert_eq!(options.bundle_id, "com.acmeaudio.myplugin");
+    let options = PluginOptions::defaults("Foo", Some("42 Audio"));
+    assert_eq!(options.manufacturer_code, "M42a");
+    let options = PluginOptions::defaults("Foo", Some("!!! ???"));
+    assert_eq!(options.manufacturer_code, "Manu");
+}
+
+#[test]
+fn test_plugin_metadata_in_cmakelists() {
+    let project_path = PathBuf::from("/tmp/plugin_metadata_test_project");
+    let _ = fs::remove_dir_all(&project_path); // Clean up before running the test
+    fs::create_dir_all(&project_path).expect("Failed to create test project directory");
+
+    let context = Context {
+        project_name: String::from("Delay"),
+        project_path: project_path.clone(),
+        template_name: Some(String::from("AudioPlugin")),
+        company: Some(String::from("Acme Audio")),
+        plugin: Some(PluginOptions {
+            manufacturer_code: String::from("Acme"),
+            plugin_code: String::from("Dly1"),
+            bundle_id: String::from("com.acme.delay"),
+            kind: PluginKind::Synth,
+            midi_input: true,
+            midi_output: false,
+            formats: vec![String::from("VST3"), String::from("LV2")],
+            apvts: false,
+        }),
+        ..Default::default()
+    };
+    create_source_files(&context).expect("Failed to create source files");
+
+    let content = fs::read_to_string(project_path.join("src").join("CMakeLists.txt"))
+        .expect("Failed to read src/CMakeLists.txt");
+    for expected in [
+        "COMPANY_NAME \"Acme Audio\"",
+        "BUNDLE_ID com.acme.delay",
+        "IS_SYNTH TRUE",
+        "IS_MIDI_EFFECT FALSE",
+        "NEEDS_MIDI_INPUT TRUE",
+        "NEEDS_MIDI_OUTPUT FALSE",
+        "PLUGIN_MANUFACTURER_CODE Acme",
+        "PLUGIN_CODE Dly1",
+        "FORMATS VST3 LV2",
+    ] {
+        assert!(
+            content.contains(expected),
+            "Missing '{}' in:\n{}",
+            expected,
+            content
+        );
+    }
+    assert!(
+        !content.contains("{{"),
+        "Unrendered placeholder in:\n{}",
+        content
+    );
+
+    // The plugin metadata is remembered in the manifest.
+    let manifest = Manifest::from_context(&context);
+    manifest
+        .save(&project_path)
+        .expect("Failed to save manifest");
+    let loaded = Manifest::load(&project_path).expect("Failed to load manifest");
+    assert_eq!(loaded.plugin, context.plugin);
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
+
+#[test]
+fn test_plugin_kind_selects_sources() {
+    let sources = |kind: PluginKind| {
+        let mut plugin = PluginOptions::defaults("Voices", None);
+        plugin.kind = kind;
+        plugin.midi_input = kind.default_midi_input();
+        plugin.midi_output = kind.default_midi_output();
+        let context = Context {
+            project_name: String::from("Voices"),
+            template_name: Some(String::from("AudioPlugin")),
+            plugin: Some(plugin),
+            ..Default::default()
+        };
+        let files = generate_source_files(&context).expect("Failed to render AudioPlugin");
+        let read = |name: &str| {
+            let file = files
+                .iter()
+                .find(|file| file.path.ends_with(name))
+                .unwrap_or_else(|| panic!("Missing {}", name));
+            String::from_utf8(file.content.clone()).unwrap()
+        };
+        (
+            read("PluginProcessor.cpp"),
+            read("PluginEditor.h"),
+            read("CMakeLists.txt"),
+        )
+    };
+
+    let (processor, editor, cmakelists) = sources(PluginKind::Effect);
+    assert!(!processor.contains("juce::Synthesiser") && !processor.contains("setNoteNumber"));
+    assert!(!editor.contains("MidiKeyboardComponent"));
+    assert!(cmakelists.contains("IS_SYNTH FALSE"));
+    assert!(cmakelists.contains("NEEDS_MIDI_INPUT FALSE"));
+
+    let (processor, editor, cmakelists) = sources(PluginKind::Synth);
+    assert!(processor.contains("synth.addVoice (new SineVoice())"));
+    assert!(editor.contains("juce::MidiKeyboardComponent keyboardComponent;"));
+    for expected in [
+        "IS_SYNTH TRUE",
+        "NEEDS_MIDI_INPUT TRUE",
+        "NEEDS_MIDI_OUTPUT FALSE",
+        "IS_MIDI_EFFECT FALSE",
+    ] {
+        assert!(cmakelists.contains(expected), "{}", cmakelists);
+    }
+
+    let (processor, editor, cmakelists) = sources(PluginKind::MidiEffect);
+    assert!(processor.contains("message.setNoteNumber (note);"));
+    assert!(editor.contains("juce::Slider semitonesSlider;"));
+    for expected in [
+        "IS_SYNTH FALSE",
+        "NEEDS_MIDI_INPUT TRUE",
+        "NEEDS_MIDI_OUTPUT TRUE",
+        "IS_MIDI_EFFECT TRUE",
+    ] {
+        assert!(cmakelists.contains(expected), "{}", cmakelists);
+    }
+}
+
+#[test]
+fn test_plugin_with_apvts() {
+    let render = |kind: PluginKind| {
+        let mut plugin = PluginOptions::defaults("Gainer", None);
+        plugin.kind = kind;
+        plugin.apvts = true;
+        let context = Context {
+            project_name: String::from("Gainer"),
+            template_name: Some(String::from("AudioPlugin")),
+            plugin: Some(plugin),
+            ..Default::default()
+        };
+        generate_source_files(&context).expect("Failed to render AudioPlugin")
+    };
+    let read = |files: &[GeneratedFile], name: &str| {
+        let file = files
+            .iter()
+            .find(|file| file.path.ends_with(name))
+            .unwrap_or_else(|| panic!("Missing {}", name));
+        String::from_utf8(file.content.clone()).unwrap()
+    };
+
+    for kind in [
+        PluginKind::Effect,
+        PluginKind::Synth,
+        PluginKind::MidiEffect,
+    ] {
+        let files = render(kind);
+        let header = read(&files, "PluginProcessor.h");
+        let source = read(&files, "PluginProcessor.cpp");
+        assert!(header.contains("    juce::AudioProcessorValueTreeState parameters;\n"));
+        assert!(header.contains(
+            "    static juce::AudioProcessorValueTreeState::ParameterLayout createParameterLayout();\n"
+        ));
+        for expected in [
+            "),\n       parameters (*this, nullptr, \"Parameters\", createParameterLayout())\n{\n",
+            "AudioPluginAudioProcessor::createParameterLayout()\n{\n",
+            "    // JUMAKE_PARAMETERS_BEGIN\n",
+            "    // JUMAKE_PARAMETERS_END\n",
+            "    copyXmlToBinary (*xml, destData);\n}",
+            "        parameters.replaceState (juce::ValueTree::fromXml (*xmlState));\n}",
+        ] {
+            assert!(
+                source.contains(expected),
+                "Missing '{}' in:\n{}",
+                expected,
+                source
+            );
+        }
+        assert!(!source.contains("juce::ignoreUnused (destData)"));
+        assert_eq!(
+            header.contains("    inline constexpr auto gain = \"gain\";\n"),
+            kind != PluginKind::MidiEffect,
+            "{}",
+            header
+        );
+        // The parameters only need juce_audio_processors, which the template links already.
+        assert!(!header.contains("juce_dsp"), "{}", header);
+        assert!(!read(&files, "CMakeLists.txt").contains("juce::juce_dsp"));
+    }
+
+    let source = read(&render(PluginKind::Effect), "PluginProcessor.cpp");
+    assert!(
+        source.contains("{\n    gain = parameters.getRawParameterValue (ParameterIDs::gain);\n}\n")
+    );
+    assert!(source.contains(
+        "    buffer.applyGainRamp (0, buffer.getNumSamples(), previousGain, currentGain);\n    previousGain = currentGain;\n}\n"
+    ));
+}
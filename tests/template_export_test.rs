@@ -0,0 +1,104 @@
+// tests/template_export_test.rs
+
+use jumake::{
+    context::Context,
+    create_files::{create_source_files, find_builtin_template, BUILTIN_TEMPLATES},
+    placeholders::placeholder_names,
+    templates::export_builtin_template,
+};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn test_find_builtin_template() {
+    for template in &BUILTIN_TEMPLATES {
+        assert!(!template.description.is_empty());
+        assert!(template
+            .files
+            .iter()
+            .any(|(file_name, _)| *file_name == "src/CMakeLists.txt"));
+    }
+    assert_eq!(
+        find_builtin_template("audio-plugin").map(|t| t.name),
+        Some("AudioPlugin")
+    );
+    assert_eq!(
+        find_builtin_template("GuiApplication").map(|t| t.name),
+        Some("GuiApplication")
+    );
+    assert!(find_builtin_template("Synth").is_none());
+
+    // The command line names are derived from the same list.
+    let cli_names: Vec<String> = BUILTIN_TEMPLATES.iter().map(|t| t.cli_name()).collect();
+    assert_eq!(
+        cli_names,
+        [
+            "gui-application",
+            "audio-plugin",
+            "console-app",
+            "static-library",
+            "juce-module",
+            "plugin-host"
+        ]
+    );
+    for cli_name in &cli_names {
+        assert!(find_builtin_template(cli_name).is_some(), "{}", cli_name);
+    }
+
+    let names = placeholder_names("{{company}} {{ plugin_code }} {{company}} ${PROJECT_NAME}");
+    assert_eq!(
+        names.into_iter().collect::<Vec<_>>(),
+        ["company", "plugin_code"]
+    );
+}
+
+#[test]
+fn test_exported_template_matches_builtin() {
+    let root = PathBuf::from("/tmp/template_export_test");
+    let _ = fs::remove_dir_all(&root); // Clean up before running the test
+    let template = find_builtin_template("AudioPlugin").unwrap();
+
+    let exported = export_builtin_template(template, &root.join("MyPlugin"))
+        .expect("Failed to export template");
+    assert_eq!(exported.name(), "MyPlugin");
+    assert!(exported.manifest.template.plugin);
+    assert!(export_builtin_template(template, &root.join("MyPlugin")).is_err());
+
+    // Rendering the exported template gives the same project as the built-in one.
+    let render = |project: &str, template_dir: Option<PathBuf>| {
+        let context = Context {
+            project_name: String::from("Delay"),
+            project_path: root.join(project),
+            template_name: Some(String::from("AudioPlugin")),
+            template_dir,
+            ..Default::default()
+        };
+        fs::create_dir_all(&context.project_path).unwrap();
+        create_source_files(&context).expect("Failed to create source files");
+        context.project_path
+    };
+    let built_in = render("BuiltIn", None);
+    let custom = render("Custom", Some(exported.path.clone()));
+    for (file_name, _) in template.files {
+        // Only the template marker names the exported template instead of the built-in one.
+        assert_eq!(
+            fs::read_to_string(built_in.join(file_name))
+                .unwrap()
+                .replace(
+                    "set(JUMAKE_TEMPLATE \"AudioPlugin\")",
+                    "set(JUMAKE_TEMPLATE \"MyPlugin\")"
+                ),
+            fs::read_to_string(custom.join(file_name)).unwrap(),
+            "{} differs",
+            file_name
+        );
+    }
+    let cmakelists = fs::read_to_string(custom.join("src/CMakeLists.txt")).unwrap();
+    assert!(
+        cmakelists.contains("set(JUMAKE_TEMPLATE \"MyPlugin\")"),
+        "{}",
+        cmakelists
+    );
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
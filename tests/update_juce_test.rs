@@ -0,0 +1,63 @@
+// tests/update_juce_test.rs
+
+mod common;
+
+use common::create_fake_juce_repo;
+use git2::Repository;
+use jumake::{
+    context::Context, create_project::create_project, manifest::Manifest, update_juce::update_juce,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn staged_gitlink(project_path: &Path) -> git2::Oid {
+    let repo = Repository::open(project_path).unwrap();
+    let index = repo.index().unwrap();
+    let entry = index.get_path(Path::new("modules/JUCE"), 0).unwrap();
+    entry.id
+}
+
+#[test]
+fn test_update_juce_moves_submodule_and_manifest() {
+    let root = PathBuf::from("/tmp/update_juce_test");
+    let _ = fs::remove_dir_all(&root);
+    let juce = create_fake_juce_repo(&root.join("JUCE.git"));
+
+    let context = Context {
+        project_name: String::from("UpdateProject"),
+        project_path: root.join("UpdateProject"),
+        template_name: Some(String::from("ConsoleApp")),
+        juce_source: juce.url.clone(),
+        juce_revision: Some(String::from("7.0.0")),
+        ..Default::default()
+    };
+    create_project(&context).expect("Failed to create project");
+
+    let update = update_juce(&context, Some("develop")).expect("Failed to update JUCE");
+    assert_eq!(update.old_commit, juce.first_commit);
+    assert_eq!(update.new_commit, juce.develop_commit);
+    assert_eq!(update.old_version.as_deref(), Some("7.0.0"));
+    assert_eq!(update.new_version.as_deref(), Some("8.1.0"));
+    assert_eq!(staged_gitlink(&context.project_path), juce.develop_commit);
+    let manifest = Manifest::load(&context.project_path).unwrap();
+    assert_eq!(manifest.juce.revision.as_deref(), Some("develop"));
+    let gitmodules = fs::read_to_string(context.project_path.join(".gitmodules")).unwrap();
+    assert!(gitmodules.contains("branch = develop"));
+
+    // Moving to a tag drops the branch from .gitmodules again.
+    let update = update_juce(&context, Some("8.0.0")).expect("Failed to update JUCE");
+    assert_eq!(update.new_commit, juce.second_commit);
+    assert_eq!(staged_gitlink(&context.project_path), juce.second_commit);
+    let gitmodules = fs::read_to_string(context.project_path.join(".gitmodules")).unwrap();
+    assert!(!gitmodules.contains("branch"));
+
+    // Local modifications inside JUCE are never overwritten.
+    fs::write(
+        context.project_path.join("modules/JUCE/CMakeLists.txt"),
+        "patched\n",
+    )
+    .unwrap();
+    assert!(update_juce(&context, Some("7.0.0")).is_err());
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
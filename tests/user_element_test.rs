@@ -0,0 +1,125 @@
+// tests/user_element_test.rs
+
+mod common;
+
+use common::create_console_project;
+use jumake::{
+    create_files::{add_class, plan_class, ClassOptions},
+    templates::{find_user_elements, UserElement, PROJECT_ELEMENTS_DIR},
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn write_widget_element(dir: &Path) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(
+        dir.join("element.toml"),
+        "[element]\nname = \"widget\"\nsuffix = \"Widget\"\nmodules = [\"juce::juce_gui_basics\", \"juce::juce_dsp\"]\n\n[files]\nheader = \"Widget.h\"\nsource = \"Widget.cpp\"\ntest = \"WidgetTest.cpp\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("Widget.h"),
+        "#pragma once\n\nnamespace {{namespace}}\n{\nclass {{class_name}};\n}\n",
+    )
+    .unwrap();
+    fs::write(dir.join("Widget.cpp"), "#include \"{{class_name}}.h\"\n").unwrap();
+    fs::write(
+        dir.join("WidgetTest.cpp"),
+        "#include \"../src/{{class_name}}.h\"\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_add_user_element() {
+    let project_path = PathBuf::from("/tmp/user_element_test_project");
+    let context = create_console_project(&project_path);
+    assert_eq!(PROJECT_ELEMENTS_DIR, ".jumake/templates/elements");
+    write_widget_element(&project_path.join(".jumake/templates/elements/widget"));
+
+    add_class(&context, "widget", "Knob", &ClassOptions::default())
+        .expect("Failed to add user element");
+
+    let header = fs::read_to_string(project_path.join("src/KnobWidget.h")).unwrap();
+    assert!(header.contains("class KnobWidget;"), "{}", header);
+    let test = fs::read_to_string(project_path.join("tests/KnobWidgetTest.cpp")).unwrap();
+    assert_eq!(test, "#include \"../src/KnobWidget.h\"\n");
+    let cmakelists = fs::read_to_string(project_path.join("src/CMakeLists.txt")).unwrap();
+    for expected in [
+        "KnobWidget.cpp\n        # JUMAKE_SOURCES_END",
+        "        juce::juce_core\n        juce::juce_gui_basics\n        juce::juce_dsp\n    PUBLIC",
+    ] {
+        assert!(
+            cmakelists.contains(expected),
+            "Missing '{}' in:\n{}",
+            expected,
+            cmakelists
+        );
+    }
+
+    // Nothing builds the test yet, so the user is told to add it to a target.
+    let changes = plan_class(&context, "widget", "Fader", &ClassOptions::default()).unwrap();
+    assert!(
+        changes
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("not part of any CMake target")),
+        "{:?}",
+        changes.warnings
+    );
+
+    assert!(add_class(&context, "widget", "Knob", &ClassOptions::default()).is_err());
+
+    // Tests mirror the class's sub-directory, so a same-named class elsewhere is fine.
+    add_class(&context, "widget", "dsp/Knob", &ClassOptions::default())
+        .expect("Failed to add user element in a sub-directory");
+    assert!(project_path.join("src/dsp/KnobWidget.h").exists());
+    assert!(project_path.join("tests/dsp/KnobWidgetTest.cpp").exists());
+    let error = add_class(&context, "gadget", "Knob", &ClassOptions::default())
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(error.contains("element.toml"), "{}", error);
+
+    fs::remove_dir_all(&project_path).expect("Failed to clean up test project directory");
+}
+
+#[test]
+fn test_invalid_user_elements() {
+    let dir = PathBuf::from("/tmp/invalid_user_element_test");
+    let _ = fs::remove_dir_all(&dir); // Clean up before running the test
+    write_widget_element(&dir.join("widget"));
+    assert_eq!(
+        find_user_elements(std::slice::from_ref(&dir))
+            .unwrap()
+            .len(),
+        1
+    );
+
+    fs::remove_file(dir.join("widget/WidgetTest.cpp")).unwrap();
+    let error = UserElement::load(&dir.join("widget")).err().unwrap();
+    assert!(error.to_string().contains("WidgetTest.cpp"), "{}", error);
+
+    write_widget_element(&dir.join("widget"));
+    let manifest = fs::read_to_string(dir.join("widget/element.toml")).unwrap();
+    fs::write(
+        dir.join("widget/element.toml"),
+        manifest.replace("\"juce::juce_dsp\"", "\"juce_dsp\""),
+    )
+    .unwrap();
+    // A broken element is skipped and doesn't hide the others.
+    write_widget_element(&dir.join("gadget"));
+    let gadget = fs::read_to_string(dir.join("gadget/element.toml")).unwrap();
+    fs::write(
+        dir.join("gadget/element.toml"),
+        gadget.replace("name = \"widget\"", "name = \"gadget\""),
+    )
+    .unwrap();
+    let elements = find_user_elements(std::slice::from_ref(&dir)).unwrap();
+    assert_eq!(
+        elements.iter().map(UserElement::name).collect::<Vec<_>>(),
+        ["gadget"]
+    );
+
+    fs::remove_dir_all(&dir).expect("Failed to clean up test directory");
+}
@@ -0,0 +1,179 @@
+// tests/user_template_test.rs
+
+use jumake::{
+    context::Context,
+    create_files::create_source_files,
+    placeholders::project_variables,
+    templates::{find_user_template, find_user_templates, UserTemplate},
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn write_template(dir: &Path, name: &str) {
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(
+        dir.join("template.toml"),
+        format!(
+            r#"[template]
+name = "{name}"
+description = "In-house skeleton"
+
+[[files]]
+source = "src/CMakeLists.txt"
+
+[[files]]
+source = "Processor.cpp"
+target = "src/{{{{project_name}}}}Processor.cpp"
+
+[placeholders.author]
+description = "Who maintains the project"
+default = "Audio Team"
+"#
+        ),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("src").join("CMakeLists.txt"),
+        "juce_add_console_app(${PROJECT_NAME})\ntarget_sources(${PROJECT_NAME}\n    PRIVATE\n        # JUMAKE_SOURCES_BEGIN\n        {{project_name}}Processor.cpp\n        # JUMAKE_SOURCES_END\n)\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("Processor.cpp"),
+        "// {{project_name}} by {{author}}, (c) {{year}} {{company}}\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_find_user_templates() {
+    let root = PathBuf::from("/tmp/user_template_find_test");
+    let _ = fs::remove_dir_all(&root); // Clean up before running the test
+    write_template(&root.join("first").join("InHouse"), "InHouse");
+    write_template(&root.join("second").join("InHouse"), "InHouse");
+    write_template(&root.join("second").join("Other"), "Other");
+
+    let search_dirs = vec![
+        root.join("first"),
+        root.join("second"),
+        root.join("missing"),
+    ];
+    let templates = find_user_templates(&search_dirs).expect("Failed to find templates");
+    let names: Vec<&str> = templates.iter().map(|t| t.name()).collect();
+    assert_eq!(names, ["InHouse", "Other"]);
+    // The first search directory wins.
+    assert_eq!(templates[0].path, root.join("first").join("InHouse"));
+
+    // A search directory can also be a template itself.
+    let template = find_user_template(&[root.join("second").join("Other")], "other")
+        .expect("Failed to find templates")
+        .expect("Template not found");
+    assert_eq!(template.name(), "Other");
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_render_user_template() {
+    let root = PathBuf::from("/tmp/user_template_render_test");
+    let _ = fs::remove_dir_all(&root); // Clean up before running the test
+    let template_dir = root.join("InHouse");
+    write_template(&template_dir, "InHouse");
+    let project_path = root.join("Delay");
+    fs::create_dir_all(&project_path).unwrap();
+
+    let context = Context {
+        project_name: String::from("Delay"),
+        project_path: project_path.clone(),
+        template_name: Some(String::from("InHouse")),
+        template_dir: Some(template_dir.clone()),
+        template_variables: [(String::from("author"), String::from("Bob"))].into(),
+        company: Some(String::from("Acme")),
+        ..Default::default()
+    };
+    create_source_files(&context).expect("Failed to render user template");
+
+    let source = fs::read_to_string(project_path.join("src").join("DelayProcessor.cpp")).unwrap();
+    assert!(source.starts_with("// Delay by Bob, (c) "), "{}", source);
+    assert!(source.contains(" Acme"), "{}", source);
+    let cmake = fs::read_to_string(project_path.join("src").join("CMakeLists.txt")).unwrap();
+    assert!(
+        cmake.starts_with("set(JUMAKE_TEMPLATE \"InHouse\")\n"),
+        "{}",
+        cmake
+    );
+    assert!(cmake.contains("DelayProcessor.cpp"), "{}", cmake);
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_placeholders_cannot_leave_the_project() {
+    let root = PathBuf::from("/tmp/user_template_escape_test");
+    let _ = fs::remove_dir_all(&root); // Clean up before running the test
+    let template_dir = root.join("InHouse");
+    write_template(&template_dir, "InHouse");
+    let manifest = fs::read_to_string(template_dir.join("template.toml")).unwrap();
+    fs::write(
+        template_dir.join("template.toml"),
+        manifest.replace("src/{{project_name}}Processor.cpp", "src/{{author}}.cpp"),
+    )
+    .unwrap();
+    let template = UserTemplate::load(&template_dir).expect("Failed to load template");
+    let project_path = root.join("Delay");
+    fs::create_dir_all(&project_path).unwrap();
+
+    for author in ["../../.bashrc", "sub/../../../escaped"] {
+        let context = Context {
+            project_name: String::from("Delay"),
+            project_path: project_path.clone(),
+            template_name: Some(String::from("InHouse")),
+            template_dir: Some(template_dir.clone()),
+            template_variables: [(String::from("author"), String::from(author))].into(),
+            ..Default::default()
+        };
+        let mut variables = project_variables(&context);
+        variables.extend(context.template_variables.clone());
+        let error = template.generate(&variables).err().unwrap().to_string();
+        assert!(error.contains("inside the project"), "{}", error);
+        assert!(create_source_files(&context).is_err());
+    }
+    assert!(!root.join(".bashrc").exists());
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}
+
+#[test]
+fn test_invalid_user_templates() {
+    let root = PathBuf::from("/tmp/user_template_invalid_test");
+    let _ = fs::remove_dir_all(&root); // Clean up before running the test
+
+    let no_cmake = root.join("NoCMake");
+    write_template(&no_cmake, "NoCMake");
+    fs::remove_file(no_cmake.join("src").join("CMakeLists.txt")).unwrap();
+    let error = UserTemplate::load(&no_cmake).unwrap_err().to_string();
+    assert!(error.contains("src/CMakeLists.txt"), "{}", error);
+
+    // A broken template is skipped when looking up the others.
+    write_template(&root.join("Good"), "Good");
+    let templates =
+        find_user_templates(std::slice::from_ref(&root)).expect("Failed to find templates");
+    let names: Vec<&str> = templates.iter().map(|t| t.name()).collect();
+    assert_eq!(names, ["Good"]);
+
+    let bad_placeholder = root.join("BadPlaceholder");
+    write_template(&bad_placeholder, "BadPlaceholder");
+    fs::write(bad_placeholder.join("Processor.cpp"), "{{autor}}\n").unwrap();
+    let template = UserTemplate::load(&bad_placeholder).expect("Failed to load template");
+    let context = Context {
+        project_name: String::from("Project"),
+        project_path: root.join("Project"),
+        ..Default::default()
+    };
+    let error = template
+        .generate(&project_variables(&context))
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("{{autor}}"), "{}", error);
+
+    fs::remove_dir_all(&root).expect("Failed to clean up test directory");
+}